@@ -1,31 +1,109 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use eframe::egui;
 use rand::prelude::IndexedRandom;
 use rusqlite::{Connection, Result};
-use serde_yaml;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
+use crate::cli;
 use crate::database;
-use crate::models::{ActiveTab, Config, MemoData, MemoStatus};
+use crate::ipc;
+use crate::journal::{self, Journal, JournalEntry, UndoToast};
+use crate::models::{
+    ActiveTab, AppState, Config, EvictionPolicy, MemoData, MemoRevision, MemoStatus, SearchHit,
+    SpotlightStrategy, WakeTarget, Workspace,
+};
+use crate::query::Query;
+use crate::recurrence::Recurrence;
+use crate::review::ReviewAnswer;
+use crate::schedule;
+use crate::staged::StagedState;
+use crate::tags;
+
+// Wait after a memo's first spotlight under the spaced-repetition strategy;
+// it doubles with every further showing
+const SPACED_SPOTLIGHT_HOURS: i64 = 24;
+
+// Number of previous spotlight memos remembered for "back" and the history list
+const SPOTLIGHT_HISTORY_LEN: usize = 20;
+
+// How long "not interested" keeps a memo out of the cold spotlight
+const SPOTLIGHT_HIDE_DAYS: i64 = 30;
+
+// How often delayed memos are woken and idle memos decayed in the workspaces
+// that are not shown
+const BACKGROUND_WORKSPACE_INTERVAL: Duration = Duration::from_secs(30);
+
+// What to do with the memos selected in the catch-up dialog
+#[derive(Debug, Clone, Copy)]
+pub enum CatchUpAction {
+    Wake, // To each memo's wake target, as if it had woken on time
+    Snooze(DateTime<Utc>),
+    Cold,
+    Done,
+}
+
+// One cold spotlight slot. Each slot rotates on its own timer; the timers
+// are staggered so the slots don't all change at once.
+#[derive(Debug, Clone, Default)]
+pub struct SpotlightSlot {
+    pub memo: Option<i32>,
+    last_update: Option<Instant>,
+}
+
+// Results of the last full-text search, reused until the query or memos change
+struct SearchCache {
+    fts_query: String,
+    generation: u64,
+    ranked: Vec<i32>, // Memo ids, best match first
+    hits: HashMap<i32, SearchHit>,
+}
 
 pub struct MemoApp {
     db: Connection,
+    pub workspaces: Vec<Workspace>,
+    pub workspace_id: i32, // Current workspace; only its memos are loaded
+    pub new_workspace_name: String,
     pub hot_stack: Vec<i32>, // Stack order for hot memos (IDs from top to bottom)
     pub memos: HashMap<i32, MemoData>, // All memo data by ID
     pub new_memo_text: String,
-    pub delay_input: String,      // HH:MM format for memo delay
-    pub prev_delay_input: String, // Previous delay input for tracking changes
+    pub delay_input: String, // HH:MM format for memo delay
     pub config: Config,
 
     pub active_tab: ActiveTab,
     pub cold_search: String,
     pub done_search: String,
-    pub current_spotlight_memo: Option<i32>,
-    last_spotlight_update: Option<Instant>,
+    pub global_search: String,
+    pub snooze_input: String,    // Custom duration in the snooze menu
+    pub repeat_input: String,    // Custom rule in the repeat menu
+    pub tag_filter: Vec<String>, // Tabs only show memos with all of these tags
+    search_cache: Option<SearchCache>,
+    highlighted_search: Option<String>, // FTS query of the result list shown this frame
+    memos_generation: u64, // Bumped whenever memos change, to invalidate cached searches
+    pub editing_memo: Option<i32>, // Memo currently being edited inline
+    pub edit_text: String,
+    pub edit_focus_pending: bool,
+    pub history_memo: Option<i32>, // Memo whose revision history is open
+    pub history_revisions: Vec<MemoRevision>,
+    pub history_compare: (usize, usize), // Versions to diff: 0 = current, n = nth newest revision
+    journal: Journal,
+    // Journals of the other workspaces, with their memos when last shown
+    stashed_journals: HashMap<i32, (Journal, HashMap<i32, MemoData>)>,
+    pub toast: Option<UndoToast>, // Transient "Undo" notice after destructive actions
+    pub spotlight_slots: Vec<SpotlightSlot>,
     pub spotlight_expanded_states: std::collections::HashMap<i32, bool>,
+    spotlight_queue: Vec<i32>, // Woke into the spotlight; shown before random picks
+    spotlight_history: Vec<i32>, // Previously shown spotlight memos, most recent last
+    pub catch_up: Vec<i32>,    // Delayed memos that came due before the app started
+    started_at: DateTime<Utc>,
+    pub catch_up_selected: HashSet<i32>,
+    pub catch_up_snooze: String,
+    data_version: i64, // Last seen PRAGMA data_version, to notice external writes
+    background_checked_at: Option<Instant>, // Last upkeep of the workspaces not shown
+    ipc_requests: Option<mpsc::Receiver<ipc::PendingRequest>>, // Commands from other processes
     pub always_on_top: bool,
     pub memo_input_height: f32,
     pub window_width: f32,
@@ -35,7 +113,7 @@ pub struct MemoApp {
 }
 
 impl MemoApp {
-    pub fn new() -> Result<Self> {
+    pub fn data_dir() -> PathBuf {
         // Get proper data directory
         let data_dir = dirs::data_dir()
             .unwrap_or_else(|| PathBuf::from("."))
@@ -46,10 +124,18 @@ impl MemoApp {
             eprintln!("Warning: Could not create data directory, using current directory");
         });
 
+        data_dir
+    }
+
+    pub fn new() -> Result<Self> {
+        let data_dir = Self::data_dir();
         let db_path = data_dir.join("memos.db");
         let config_path = data_dir.join("config.yaml");
         let db = Connection::open(&db_path)?;
 
+        // The GUI and command-line invocations may hold the database at the same time
+        db.busy_timeout(Duration::from_secs(2))?;
+
         // Load or create config
         let config = Self::load_config(&config_path);
 
@@ -58,18 +144,44 @@ impl MemoApp {
 
         let mut app = Self {
             db,
+            workspaces: Vec::new(),
+            workspace_id: 1,
+            new_workspace_name: String::new(),
             hot_stack: Vec::new(),
             memos: HashMap::new(),
             new_memo_text: String::new(),
             delay_input: String::from("00:00"),
-            prev_delay_input: String::from("00:00"),
             config,
             active_tab: ActiveTab::Hot,
             cold_search: String::new(),
             done_search: String::new(),
-            current_spotlight_memo: None,
-            last_spotlight_update: None,
+            global_search: String::new(),
+            snooze_input: String::new(),
+            repeat_input: String::new(),
+            tag_filter: Vec::new(),
+            search_cache: None,
+            highlighted_search: None,
+            memos_generation: 0,
+            editing_memo: None,
+            edit_text: String::new(),
+            edit_focus_pending: false,
+            history_memo: None,
+            history_revisions: Vec::new(),
+            history_compare: (1, 0),
+            journal: Journal::default(),
+            stashed_journals: HashMap::new(),
+            toast: None,
+            spotlight_slots: Vec::new(),
             spotlight_expanded_states: std::collections::HashMap::new(),
+            spotlight_queue: Vec::new(),
+            spotlight_history: Vec::new(),
+            catch_up: Vec::new(),
+            started_at: Utc::now(),
+            catch_up_selected: HashSet::new(),
+            catch_up_snooze: String::new(),
+            data_version: 0,
+            background_checked_at: None,
+            ipc_requests: None,
             always_on_top: false,
             memo_input_height: 80.0,
             window_width: 800.0,
@@ -79,6 +191,8 @@ impl MemoApp {
         };
 
         app.load_state()?;
+        app.purge_revisions();
+        app.collect_catch_up();
         Ok(app)
     }
 
@@ -121,235 +235,947 @@ impl MemoApp {
         }
     }
 
+    // Loads memos, re-deriving tags for any whose text changed outside the app
+    fn load_memos(&self) -> Result<(Vec<i32>, HashMap<i32, MemoData>)> {
+        let tx = self.db.unchecked_transaction()?;
+        let (hot_stack, mut memos) = database::load_state(&tx, self.workspace_id)?;
+        if database::sync_tags(&tx, &mut memos)? {
+            tx.commit()?;
+        }
+        Ok((hot_stack, memos))
+    }
+
     fn load_state(&mut self) -> Result<()> {
-        let (hot_stack, memos) = database::load_state(&self.db)?;
+        // Load app state
+        let state = database::load_app_state(&self.db)?;
+        self.workspaces = database::load_workspaces(&self.db)?;
+        self.workspace_id = state.workspace_id;
+        if !self.workspaces.iter().any(|w| w.id == self.workspace_id)
+            && let Some(workspace) = self.workspaces.first()
+        {
+            self.workspace_id = workspace.id;
+        }
+
+        let (hot_stack, memos) = self.load_memos()?;
         self.hot_stack = hot_stack;
         self.memos = memos;
-        database::save_hot_stack(&self.db, &self.hot_stack)?;
+        self.memos_generation += 1;
+        self.data_version = database::data_version(&self.db)?;
 
-        // Load app state
-        let (
-            memo_input_height,
-            always_on_top,
-            new_memo_text,
-            window_width,
-            window_height,
-            window_x,
-            window_y,
-        ) = database::load_app_state(&self.db)?;
-        self.memo_input_height = memo_input_height;
-        self.always_on_top = always_on_top;
-        self.new_memo_text = new_memo_text;
-        self.window_width = window_width;
-        self.window_height = window_height;
-        self.window_x = window_x;
-        self.window_y = window_y;
+        self.memo_input_height = state.memo_input_height;
+        self.always_on_top = state.always_on_top;
+        self.new_memo_text = state.new_memo_text;
+        self.window_width = state.window_width;
+        self.window_height = state.window_height;
+        self.window_x = state.window_x;
+        self.window_y = state.window_y;
 
         Ok(())
     }
 
+    // Listens for commands forwarded by `memo-stack <command>` invocations
+    pub fn start_ipc(&mut self, ctx: egui::Context) {
+        self.ipc_requests = ipc::start_server(ctx);
+    }
+
+    fn process_ipc_requests(&mut self) {
+        let Some(receiver) = &self.ipc_requests else {
+            return;
+        };
+        let requests: Vec<ipc::PendingRequest> = receiver.try_iter().collect();
+        for request in requests {
+            // The caller gave up waiting and ran the command itself
+            if !request.claim() {
+                continue;
+            }
+            let result = cli::execute(self, &request.invocation);
+            let _ = request.reply.send(result);
+        }
+    }
+
+    // Reloads memos if another process (sqlite3, a sync tool, the CLI without a
+    // window) has written to the database since we last looked
+    pub fn check_external_changes(&mut self) -> Result<()> {
+        let version = database::data_version(&self.db)?;
+        if version == self.data_version {
+            return Ok(());
+        }
+        self.data_version = version;
+
+        self.workspaces = database::load_workspaces(&self.db)?;
+        let (hot_stack, mut memos) = self.load_memos()?;
+
+        // Carry over UI-only state
+        for (id, memo) in memos.iter_mut() {
+            if let Some(old) = self.memos.get(id) {
+                memo.expanded = old.expanded;
+            }
+        }
+        self.spotlight_expanded_states
+            .retain(|id, _| memos.contains_key(id));
+
+        // Undoing an entry for a memo changed elsewhere could overwrite that change
+        let changed = journal::changed_memo_ids(&self.memos, &memos);
+        if self.journal.forget(&changed) {
+            self.toast = None;
+        }
+        self.purge_revisions();
+
+        self.hot_stack = hot_stack;
+        self.memos = memos;
+        self.memos_generation += 1;
+        self.close_stale_editors();
+        self.refresh_history()
+    }
+
+    // Reloads the open history panel's revisions, keeping the selected
+    // comparison where possible
+    fn refresh_history(&mut self) -> Result<()> {
+        if let Some(id) = self.history_memo {
+            self.history_revisions = database::load_revisions(&self.db, id)?;
+            let last = self.history_revisions.len();
+            self.history_compare = (
+                self.history_compare.0.min(last),
+                self.history_compare.1.min(last),
+            );
+        }
+        Ok(())
+    }
+
+    // Memo ids matching the query's text terms, best match first, or None if
+    // it has no text terms. Results are cached until the query or memos change.
+    pub fn search_ranked(&mut self, query: &Query) -> Option<Vec<i32>> {
+        let fts_query = query.fts_query()?;
+
+        let current = self.search_cache.as_ref().is_some_and(|cache| {
+            cache.fts_query == fts_query && cache.generation == self.memos_generation
+        });
+        if !current {
+            let hits = database::search_memos(&self.db, &fts_query).unwrap_or_else(|e| {
+                eprintln!("Error searching memos: {}", e);
+                Vec::new()
+            });
+            self.search_cache = Some(SearchCache {
+                fts_query: fts_query.clone(),
+                generation: self.memos_generation,
+                ranked: hits.iter().map(|hit| hit.id).collect(),
+                hits: hits.into_iter().map(|hit| (hit.id, hit)).collect(),
+            });
+        }
+
+        // Highlight matches in the list being rendered
+        self.highlighted_search = Some(fts_query);
+        self.search_cache.as_ref().map(|cache| cache.ranked.clone())
+    }
+
+    // Called at the start of each frame; lists that search set it again
+    pub fn clear_search_highlight(&mut self) {
+        self.highlighted_search = None;
+    }
+
+    // The search match for a memo in a result list rendered this frame, if any
+    pub fn search_hit(&self, memo: &MemoData) -> Option<&SearchHit> {
+        self.search_cache
+            .as_ref()
+            .filter(|cache| {
+                cache.generation == self.memos_generation
+                    && self.highlighted_search.as_ref() == Some(&cache.fts_query)
+            })
+            .and_then(|cache| cache.hits.get(&memo.id))
+    }
+
+    pub fn matches_tag_filter(&self, memo: &MemoData) -> bool {
+        self.tag_filter
+            .iter()
+            .all(|tag| tags::has_tag(&memo.tags, tag))
+    }
+
+    pub fn toggle_tag_filter(&mut self, tag: &str) {
+        if let Some(pos) = self.tag_filter.iter().position(|t| t == tag) {
+            self.tag_filter.remove(pos);
+        } else {
+            self.tag_filter.push(tag.to_string());
+        }
+
+        // Pick a new spotlight right away in slots whose memo is filtered out
+        let filtered_out: Vec<bool> = self
+            .spotlight_slots
+            .iter()
+            .map(|slot| {
+                slot.memo
+                    .and_then(|id| self.memos.get(&id))
+                    .is_some_and(|memo| !self.matches_tag_filter(memo))
+            })
+            .collect();
+        for (slot, filtered_out) in self.spotlight_slots.iter_mut().zip(filtered_out) {
+            if filtered_out {
+                slot.last_update = None;
+            }
+        }
+    }
+
+    // Every tag in use with the number of memos that have it, by name
+    pub fn tag_counts(&self) -> Vec<(String, usize)> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for memo in self.memos.values() {
+            for tag in &memo.tags {
+                *counts.entry(tag.as_str()).or_default() += 1;
+            }
+        }
+        let mut counts: Vec<(String, usize)> = counts
+            .into_iter()
+            .map(|(tag, count)| (tag.to_string(), count))
+            .collect();
+        counts.sort();
+        counts
+    }
+
     pub fn save_app_state(&self) -> Result<()> {
         database::save_app_state(
             &self.db,
-            self.memo_input_height,
-            self.always_on_top,
-            &self.new_memo_text,
-            self.window_width,
-            self.window_height,
-            self.window_x,
-            self.window_y,
+            &AppState {
+                memo_input_height: self.memo_input_height,
+                always_on_top: self.always_on_top,
+                new_memo_text: self.new_memo_text.clone(),
+                window_width: self.window_width,
+                window_height: self.window_height,
+                window_x: self.window_x,
+                window_y: self.window_y,
+                workspace_id: self.workspace_id,
+            },
         )
     }
 
+    pub fn current_workspace(&self) -> Option<&Workspace> {
+        self.workspaces.iter().find(|w| w.id == self.workspace_id)
+    }
+
+    // The current workspace's hot stack limit
+    pub fn max_hot_count(&self) -> usize {
+        self.current_workspace()
+            .and_then(|w| w.max_hot_count)
+            .unwrap_or(self.config.max_hot_count)
+    }
+
+    // Shows another workspace and opens it again next time
+    pub fn switch_workspace(&mut self, id: i32) -> Result<()> {
+        self.open_workspace(id)?;
+        self.save_app_state()?;
+
+        // Memos moved in while this workspace was not shown may not fit
+        self.trim_hot_stack()
+    }
+
+    // Shows another workspace without remembering it as the one open last
+    // or changing any memos (e.g. for `--workspace`). The spotlight and other
+    // state tied to the previous workspace's memos start over.
+    pub fn open_workspace(&mut self, id: i32) -> Result<()> {
+        if id == self.workspace_id || !self.workspaces.iter().any(|w| w.id == id) {
+            return Ok(());
+        }
+        let previous = self.workspace_id;
+        self.workspace_id = id;
+
+        let (hot_stack, memos) = self.load_memos()?;
+        let old_memos = std::mem::replace(&mut self.memos, memos);
+        self.hot_stack = hot_stack;
+        self.memos_generation += 1;
+        self.data_version = database::data_version(&self.db)?;
+
+        // Each workspace keeps its own undo history. Entries for memos that
+        // changed while the workspace was not shown are dropped.
+        let old_journal = std::mem::take(&mut self.journal);
+        self.stashed_journals
+            .insert(previous, (old_journal, old_memos));
+        if let Some((mut journal, memos)) = self.stashed_journals.remove(&id) {
+            journal.forget(&journal::changed_memo_ids(&memos, &self.memos));
+            self.journal = journal;
+        }
+        self.purge_revisions();
+        self.toast = None;
+        self.tag_filter.clear();
+        self.spotlight_slots.clear();
+        self.spotlight_expanded_states.clear();
+        self.spotlight_queue.clear();
+        self.spotlight_history.clear();
+        self.close_stale_editors();
+        self.collect_catch_up();
+        Ok(())
+    }
+
+    // Creates a workspace and switches to it
+    pub fn add_workspace(&mut self, name: &str) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Ok(());
+        }
+        if self.workspaces.iter().any(|w| w.name == name) {
+            self.show_notice(format!("A workspace named {} already exists", name));
+            return Ok(());
+        }
+        let id = database::add_workspace(&self.db, name)?;
+        self.workspaces = database::load_workspaces(&self.db)?;
+        self.switch_workspace(id)
+    }
+
+    // Sets the current workspace's hot stack limit (None = config default)
+    pub fn set_max_hot_count(&mut self, max_hot_count: Option<usize>) -> Result<()> {
+        // The new limit and the evictions it causes are saved together
+        let evicted = self.mutate(None, |staged| {
+            database::update_workspace_max_hot_count(
+                staged.db,
+                staged.workspace_id,
+                max_hot_count,
+            )?;
+            staged.max_hot_count = max_hot_count.unwrap_or(staged.config.max_hot_count);
+            staged.trim_hot_stack()
+        })?;
+        let workspace_id = self.workspace_id;
+        if let Some(workspace) = self.workspaces.iter_mut().find(|w| w.id == workspace_id) {
+            workspace.max_hot_count = max_hot_count;
+        }
+        self.report_evicted(&evicted);
+        Ok(())
+    }
+
+    fn trim_hot_stack(&mut self) -> Result<()> {
+        let evicted = self.mutate(None, |staged| staged.trim_hot_stack())?;
+        self.report_evicted(&evicted);
+        Ok(())
+    }
+
+    pub fn move_to_workspace(&mut self, id: i32, workspace_id: i32) -> Result<()> {
+        let Some(title) = self.memos.get(&id).map(|memo| memo.title.clone()) else {
+            return Ok(());
+        };
+        let Some(name) = self
+            .workspaces
+            .iter()
+            .find(|w| w.id == workspace_id)
+            .map(|w| w.name.clone())
+        else {
+            return Ok(());
+        };
+        if workspace_id == self.workspace_id {
+            return Ok(());
+        }
+        self.mutate(Some("Move to workspace"), |staged| {
+            staged.set_workspace(id, workspace_id)
+        })?;
+        self.close_stale_editors();
+        self.show_undo_toast(format!("Moved to {}: {}", name, title));
+        Ok(())
+    }
+
+    // Runs a memo operation atomically. The operation works on a staged copy of
+    // the state inside one transaction; the copy replaces the live state only
+    // after the commit succeeds. Labelled operations are recorded for undo.
+    fn mutate<T>(
+        &mut self,
+        label: Option<&str>,
+        op: impl FnOnce(&mut StagedState) -> Result<T>,
+    ) -> Result<T> {
+        let tx = self.db.unchecked_transaction()?;
+        let mut staged = StagedState {
+            db: &tx,
+            config: &self.config,
+            workspace_id: self.workspace_id,
+            max_hot_count: self.max_hot_count(),
+            hot_stack: self.hot_stack.clone(),
+            memos: self.memos.clone(),
+        };
+
+        // Dropping the transaction on error rolls it back
+        let result = op(&mut staged)?;
+        let StagedState {
+            hot_stack,
+            mut memos,
+            ..
+        } = staged;
+        tx.commit()?;
+
+        // Recording may drop old entries, whose deleted memos lose their history
+        if let Some(label) = label
+            && let Some(entry) =
+                JournalEntry::from_states(label, &self.hot_stack, &self.memos, &hot_stack, &memos)
+            && self.journal.record(entry)
+        {
+            self.purge_revisions();
+        }
+
+        // Memos moved to another workspace leave this one
+        let workspace_id = self.workspace_id;
+        memos.retain(|_, memo| memo.workspace_id == workspace_id);
+
+        self.hot_stack = hot_stack;
+        self.memos = memos;
+        self.memos_generation += 1;
+        Ok(result)
+    }
+
+    fn show_undo_toast(&mut self, message: String) {
+        self.toast = Some(UndoToast {
+            message,
+            shown_at: Instant::now(),
+            undoable: true,
+        });
+    }
+
+    fn show_notice(&mut self, message: String) {
+        self.toast = Some(UndoToast {
+            message,
+            shown_at: Instant::now(),
+            undoable: false,
+        });
+    }
+
+    fn show_cold_toast(&mut self, evicted: Option<i32>) {
+        if let Some(memo) = evicted.and_then(|id| self.memos.get(&id)) {
+            let message = format!("Moved to cold: {}", memo.title);
+            self.show_undo_toast(message);
+        }
+    }
+
+    fn report_evicted(&mut self, evicted: &[i32]) {
+        match evicted {
+            [] => {}
+            [id] => self.show_cold_toast(Some(*id)),
+            _ => self.show_notice(format!("Moved {} memos to cold", evicted.len())),
+        }
+    }
+
     pub fn add_memo(
         &mut self,
         title: String,
         body: String,
-        delay_minutes: Option<u32>,
-    ) -> Result<()> {
-        let new_id = database::add_memo(&self.db, &title, &body, delay_minutes)?;
+        wake_at: Option<DateTime<Utc>>,
+    ) -> Result<i32> {
+        let (new_id, evicted) = self.mutate(Some("Add memo"), |staged| {
+            staged.insert_memo(title, body, wake_at)
+        })?;
+        self.show_cold_toast(evicted);
+        Ok(new_id)
+    }
 
-        // Add to memos map
+    pub fn move_to_cold(&mut self, id: i32) -> Result<()> {
+        self.mutate(Some("Move to cold"), |staged| staged.set_cold(id))?;
+        self.show_cold_toast(Some(id));
+        Ok(())
+    }
+
+    pub fn move_to_done(&mut self, id: i32) -> Result<()> {
+        let next = self.mutate(Some("Move to done"), |staged| staged.set_done(id))?;
+        if let Some(memo) = self.memos.get(&id) {
+            let mut message = format!("Moved to done: {}", memo.title);
+            if let Some(wake_at) = next.and_then(|next| self.memos.get(&next)?.wake_at) {
+                message.push_str(&format!(
+                    " (repeats {})",
+                    schedule::format_wake_time(wake_at, Utc::now())
+                ));
+            }
+            self.show_undo_toast(message);
+        }
+        Ok(())
+    }
+
+    pub fn set_wake_target(&mut self, id: i32, target: WakeTarget) -> Result<()> {
+        self.mutate(Some("Change wake target"), |staged| {
+            staged.set_wake_target(id, target)
+        })
+    }
+
+    // Cold memos due for review, never-reviewed and most overdue first
+    pub fn review_queue(&self) -> Vec<i32> {
         let now = Utc::now();
-        let status = if delay_minutes.is_some() {
-            MemoStatus::Delayed
-        } else {
-            MemoStatus::Hot
-        };
+        let mut due: Vec<&MemoData> = self
+            .memos
+            .values()
+            .filter(|memo| {
+                memo.status == MemoStatus::Cold
+                    && memo.review.is_due(now)
+                    && self.matches_tag_filter(memo)
+            })
+            .collect();
+        due.sort_by_key(|memo| (memo.review.due_at, memo.creation_date));
+        due.iter().map(|memo| memo.id).collect()
+    }
 
-        self.memos.insert(
-            new_id,
-            MemoData {
-                id: new_id,
-                title,
-                body,
-                status,
-                creation_date: now,
-                moved_to_done_date: None,
-                delay_minutes,
-                expanded: false,
-            },
-        );
+    // When the next cold memo becomes due, if none is due now
+    pub fn next_review_at(&self) -> Option<DateTime<Utc>> {
+        self.memos
+            .values()
+            .filter(|memo| memo.status == MemoStatus::Cold && self.matches_tag_filter(memo))
+            .filter_map(|memo| memo.review.due_at)
+            .min()
+    }
 
-        // Only add to hot stack if it's not delayed
-        if status == MemoStatus::Hot {
-            // Add to front of hot stack
-            self.hot_stack.insert(0, new_id);
+    pub fn answer_review(&mut self, id: i32, answer: ReviewAnswer) -> Result<()> {
+        let Some(memo) = self.memos.get(&id) else {
+            return Ok(());
+        };
+        let title = memo.title.clone();
+        let review = memo.review.answer(answer, Utc::now());
+
+        let label = match answer {
+            ReviewAnswer::Promote => "Review: promote",
+            ReviewAnswer::Keep => "Review: keep",
+            ReviewAnswer::Done => "Review: done",
+            ReviewAnswer::Drop => "Review: drop",
+        };
+        let message = match answer {
+            ReviewAnswer::Promote => format!("Moved to hot: {}", title),
+            ReviewAnswer::Done => format!("Moved to done: {}", title),
+            ReviewAnswer::Drop => format!("Dropped: {}", title),
+            ReviewAnswer::Keep => format!(
+                "Next review of \"{}\" in {} days",
+                title, review.interval_days
+            ),
+        };
 
-            // If hot stack is too big, move the last item to cold
-            if self.hot_stack.len() > self.config.max_hot_count {
-                if let Some(moved_id) = self.hot_stack.pop() {
-                    self.move_to_cold(moved_id)?;
+        let evicted = self.mutate(Some(label), |staged| {
+            staged.set_review(id, review)?;
+            match answer {
+                ReviewAnswer::Promote => staged.set_hot(id),
+                ReviewAnswer::Done => staged.set_done(id).map(|_| None),
+                ReviewAnswer::Drop => {
+                    // A dropped routine does not come back
+                    staged.set_recurrence(id, None)?;
+                    staged.set_done(id).map(|_| None)
                 }
+                ReviewAnswer::Keep => Ok(None),
             }
-
-            database::save_hot_stack(&self.db, &self.hot_stack)?;
+        })?;
+        self.show_undo_toast(message);
+        if evicted.is_some() {
+            self.show_cold_toast(evicted);
         }
         Ok(())
     }
 
-    pub fn move_to_cold(&mut self, id: i32) -> Result<()> {
-        if let Some(memo) = self.memos.get_mut(&id) {
-            memo.status = MemoStatus::Cold;
-            database::update_memo_status(&self.db, id, MemoStatus::Cold)?;
-        }
-        self.hot_stack.retain(|&x| x != id);
-        database::save_hot_stack(&self.db, &self.hot_stack)?;
-        Ok(())
+    // Sets or clears the rule that brings a memo back after it is done
+    pub fn set_recurrence(&mut self, id: i32, recurrence: Option<Recurrence>) -> Result<()> {
+        let label = if recurrence.is_some() {
+            "Repeat"
+        } else {
+            "Stop repeating"
+        };
+        self.mutate(Some(label), |staged| staged.set_recurrence(id, recurrence))
     }
 
-    pub fn move_to_done(&mut self, id: i32) -> Result<()> {
-        if let Some(memo) = self.memos.get_mut(&id) {
-            let now = Utc::now();
-            memo.status = MemoStatus::Done;
-            memo.moved_to_done_date = Some(now);
-            database::update_memo_status(&self.db, id, MemoStatus::Done)?;
-        }
-        self.hot_stack.retain(|&x| x != id);
-        database::save_hot_stack(&self.db, &self.hot_stack)?;
+    pub fn move_to_hot(&mut self, id: i32) -> Result<()> {
+        let evicted = self.mutate(Some("Move to hot"), |staged| staged.set_hot(id))?;
+        self.show_cold_toast(evicted);
         Ok(())
     }
 
-    pub fn move_to_hot(&mut self, id: i32) -> Result<()> {
-        if let Some(memo) = self.memos.get_mut(&id) {
-            memo.status = MemoStatus::Hot;
-            memo.moved_to_done_date = None;
-            database::update_memo_status(&self.db, id, MemoStatus::Hot)?;
-
-            // Add to front of hot stack
-            self.hot_stack.insert(0, id);
-
-            // If hot stack is too big, move the last item to cold
-            if self.hot_stack.len() > self.config.max_hot_count {
-                if let Some(moved_id) = self.hot_stack.pop() {
-                    self.move_to_cold(moved_id)?;
-                }
+    // Moves a memo to Delayed until the given time (snooze), optionally
+    // changing where it goes when it wakes up
+    pub fn delay_memo(
+        &mut self,
+        id: i32,
+        until: DateTime<Utc>,
+        target: Option<WakeTarget>,
+    ) -> Result<()> {
+        self.mutate(Some("Snooze"), |staged| {
+            staged.set_delayed(id, until)?;
+            match target {
+                Some(target) => staged.set_wake_target(id, target),
+                None => Ok(()),
             }
+        })?;
+        let message = format!(
+            "Snoozed until {}",
+            schedule::format_wake_time(until, Utc::now())
+        );
+        self.show_undo_toast(message);
+        Ok(())
+    }
 
-            database::save_hot_stack(&self.db, &self.hot_stack)?;
+    pub fn delete_memo(&mut self, id: i32) -> Result<()> {
+        let title = self.memos.get(&id).map(|memo| memo.title.clone());
+        self.mutate(Some("Delete memo"), |staged| staged.remove_memo(id))?;
+        if let Some(title) = title {
+            self.show_undo_toast(format!("Deleted: {}", title));
         }
         Ok(())
     }
 
-    pub fn delete_memo(&mut self, id: i32) -> Result<()> {
-        // Remove from database
-        database::delete_memo(&self.db, id)?;
+    pub fn shift_up_in_hot(&mut self, id: i32) -> Result<()> {
+        self.mutate(Some("Shift up"), |staged| staged.shift_up(id))
+    }
 
-        // Remove from memory
-        self.memos.remove(&id);
-        self.hot_stack.retain(|&x| x != id);
+    pub fn move_to_top_in_hot(&mut self, id: i32) -> Result<()> {
+        self.mutate(Some("Move to top"), |staged| staged.move_to_top(id))
+    }
 
-        database::save_hot_stack(&self.db, &self.hot_stack)?;
-        Ok(())
+    pub fn touch_memo(&mut self, id: i32) -> Result<()> {
+        self.mutate(None, |staged| staged.touch(id))
     }
 
-    pub fn shift_up_in_hot(&mut self, id: i32) -> Result<()> {
-        if let Some(pos) = self.hot_stack.iter().position(|&x| x == id) {
-            if pos > 0 {
-                self.hot_stack.swap(pos - 1, pos);
-                database::save_hot_stack(&self.db, &self.hot_stack)?;
+    // Hot memos the user can choose to move to cold when the stack is over
+    // the limit under the ask-the-user eviction policy; empty otherwise
+    pub fn eviction_candidates(&self) -> Vec<i32> {
+        if self.config.eviction_policy != EvictionPolicy::AskUser
+            || self.hot_stack.len() <= self.max_hot_count()
+        {
+            return Vec::new();
+        }
+        self.hot_stack
+            .iter()
+            .copied()
+            .filter(|id| self.memos.get(id).is_some_and(|memo| !memo.pinned))
+            .collect()
+    }
+
+    pub fn pinned_count(&self) -> usize {
+        self.memos.values().filter(|memo| memo.pinned).count()
+    }
+
+    // Pins a hot memo or unpins it. Pinning is refused once
+    // `max_pinned_count` memos are pinned.
+    pub fn toggle_pin(&mut self, id: i32) -> Result<()> {
+        let Some(memo) = self.memos.get(&id) else {
+            return Ok(());
+        };
+        if memo.pinned {
+            return self.mutate(Some("Unpin"), |staged| staged.set_pinned(id, false));
+        }
+        if memo.status != MemoStatus::Hot || self.pinned_count() >= self.config.max_pinned_count {
+            return Ok(());
+        }
+        self.mutate(Some("Pin"), |staged| staged.set_pinned(id, true))
+    }
+
+    pub fn undo(&mut self) -> Result<()> {
+        if let Some(entry) = self.journal.take_undo() {
+            if let Err(e) = self.mutate(None, |staged| {
+                staged.apply_snapshot(&entry.before, &entry.after)
+            }) {
+                // Nothing was applied, so keep the entry available
+                self.journal.push_undo(entry);
+                return Err(e);
             }
+            self.journal.push_redo(entry);
+            self.toast = None;
+            self.close_stale_editors();
+            self.refresh_history()?;
         }
         Ok(())
     }
 
-    pub fn move_to_top_in_hot(&mut self, id: i32) -> Result<()> {
-        // Remove from current position
-        self.hot_stack.retain(|&x| x != id);
+    pub fn redo(&mut self) -> Result<()> {
+        if let Some(entry) = self.journal.take_redo() {
+            if let Err(e) = self.mutate(None, |staged| {
+                staged.apply_snapshot(&entry.after, &entry.before)
+            }) {
+                self.journal.push_redo(entry);
+                return Err(e);
+            }
+            self.journal.push_undo(entry);
+            self.close_stale_editors();
+            self.refresh_history()?;
+        }
+        Ok(())
+    }
 
-        // Add to front
-        self.hot_stack.insert(0, id);
+    // Close editors for memos that no longer exist
+    fn close_stale_editors(&mut self) {
+        if self
+            .editing_memo
+            .is_some_and(|id| !self.memos.contains_key(&id))
+        {
+            self.cancel_editing();
+        }
+        if self
+            .history_memo
+            .is_some_and(|id| !self.memos.contains_key(&id))
+        {
+            self.close_history();
+        }
+    }
 
-        database::save_hot_stack(&self.db, &self.hot_stack)?;
-        Ok(())
+    pub fn update_memo_content(&mut self, id: i32, title: String, body: String) -> Result<()> {
+        self.mutate(Some("Edit memo"), |staged| {
+            staged.update_content(id, title, body)
+        })
     }
 
-    pub fn replace_memo(&mut self, id: i32) -> Result<()> {
-        // If there's existing text in the input area, save it as a memo first
-        if !self.new_memo_text.trim().is_empty() {
-            let (title, body) = self.parse_memo_text();
-            self.add_memo(title, body, None)?;
+    // Drops the revisions of deleted memos that undo can no longer restore
+    fn purge_revisions(&self) {
+        let mut keep = self.journal.memo_ids();
+        for (journal, _) in self.stashed_journals.values() {
+            keep.extend(journal.memo_ids());
         }
+        if let Err(e) = database::purge_orphaned_revisions(&self.db, &keep) {
+            eprintln!("Error purging revisions: {}", e);
+        }
+    }
 
+    pub fn start_editing(&mut self, id: i32) {
         if let Some(memo) = self.memos.get(&id) {
-            // Format text for input field
-            self.new_memo_text = if memo.body.is_empty() {
-                memo.title.clone()
-            } else {
-                format!("{}\n{}", memo.title, memo.body)
-            };
+            // Format text the same way the memo input expects it
+            self.edit_text = Self::format_memo_text(&memo.title, &memo.body);
+            self.editing_memo = Some(id);
+            self.edit_focus_pending = true;
+        }
+    }
 
-            // Delete the original memo
-            self.delete_memo(id)?;
+    pub fn cancel_editing(&mut self) {
+        self.editing_memo = None;
+        self.edit_text.clear();
+    }
+
+    pub fn save_editing(&mut self) -> Result<()> {
+        if let Some(id) = self.editing_memo {
+            let (title, body) = Self::parse_memo_text(&self.edit_text);
+            if !title.is_empty() {
+                self.update_memo_content(id, title, body)?;
+                if self.history_memo == Some(id) {
+                    self.open_history(id)?;
+                }
+            }
         }
+        self.cancel_editing();
         Ok(())
     }
 
+    pub fn open_history(&mut self, id: i32) -> Result<()> {
+        self.history_revisions = database::load_revisions(&self.db, id)?;
+        self.history_memo = Some(id);
+        // Default to comparing the latest revision against the current text
+        self.history_compare = (1.min(self.history_revisions.len()), 0);
+        Ok(())
+    }
+
+    pub fn close_history(&mut self) {
+        self.history_memo = None;
+        self.history_revisions.clear();
+    }
+
+    pub fn restore_revision(&mut self, memo_id: i32, revision_id: i64) -> Result<()> {
+        if let Some(revision) = self
+            .history_revisions
+            .iter()
+            .find(|r| r.id == revision_id && r.memo_id == memo_id)
+            .cloned()
+        {
+            // Restoring is itself an edit, so the current text becomes a revision too
+            self.update_memo_content(memo_id, revision.title, revision.body)?;
+            self.open_history(memo_id)?;
+        }
+        Ok(())
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.journal.undo_label()
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.journal.redo_label()
+    }
+
     pub fn update_cold_spotlight(&mut self) {
-        if self.config.cold_spotlight_interval_seconds == 0 {
+        let interval = self.config.cold_spotlight_interval_seconds;
+        if interval == 0 {
             return;
         }
 
-        // Check if spotlight should be paused due to expansion
-        let is_paused = self.config.pause_spotlight_when_expanded
-            && self.current_spotlight_memo.is_some()
-            && self
-                .spotlight_expanded_states
-                .get(&self.current_spotlight_memo.unwrap())
-                .copied()
-                .unwrap_or(false);
+        let slot_count = self.config.cold_spotlight_slots;
+        if self.spotlight_slots.len() != slot_count {
+            self.spotlight_slots
+                .resize_with(slot_count, SpotlightSlot::default);
+        }
 
-        if is_paused {
+        let now = Instant::now();
+        for index in 0..slot_count {
+            // Check if this slot should be paused due to expansion
+            if self.is_spotlight_paused(index) {
+                continue;
+            }
+
+            match self.spotlight_slots[index].last_update {
+                None => {
+                    // Start each slot's timer part of an interval earlier than
+                    // the one before so the slots take turns changing
+                    self.rotate_spotlight(index);
+                    let offset = Duration::from_secs(interval * index as u64 / slot_count as u64);
+                    self.spotlight_slots[index].last_update = now.checked_sub(offset).or(Some(now));
+                }
+                Some(last_update) if now.duration_since(last_update).as_secs() >= interval => {
+                    self.rotate_spotlight(index);
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    // Memos shown in any spotlight slot
+    fn spotlight_memos(&self) -> Vec<i32> {
+        self.spotlight_slots
+            .iter()
+            .filter_map(|slot| slot.memo)
+            .collect()
+    }
+
+    // Moves a spotlight slot on to the next memo (also the "skip" action).
+    // The slot keeps its memo if there is nothing else to show.
+    pub fn rotate_spotlight(&mut self, slot: usize) {
+        if self.is_spotlight_editing(slot) {
             return;
         }
+        let next = self
+            .next_queued_spotlight()
+            .or_else(|| self.choose_spotlight_memo_id())
+            .or_else(|| self.spotlight_slots.get(slot).and_then(|slot| slot.memo));
+        self.show_in_spotlight(slot, next);
+
+        // Not recorded for undo; it only feeds the spotlight strategies
+        if let Some(id) = next
+            && let Err(e) = self.mutate(None, |staged| staged.record_spotlight(id))
+        {
+            eprintln!("Error recording spotlight: {}", e);
+        }
+    }
 
-        let now = Instant::now();
-        let should_update = match self.last_spotlight_update {
-            None => true,
-            Some(last_update) => {
-                now.duration_since(last_update).as_secs()
-                    >= self.config.cold_spotlight_interval_seconds
+    // Shows a memo in a spotlight slot, remembering the one it replaces
+    pub fn show_in_spotlight(&mut self, slot: usize, id: Option<i32>) {
+        if self.is_spotlight_editing(slot) {
+            return;
+        }
+        let Some(current) = self.spotlight_slots.get(slot).map(|slot| slot.memo) else {
+            return;
+        };
+        if let Some(current) = current
+            && Some(current) != id
+        {
+            self.spotlight_history.retain(|&x| x != current);
+            self.spotlight_history.push(current);
+            if self.spotlight_history.len() > SPOTLIGHT_HISTORY_LEN {
+                self.spotlight_history.remove(0);
+            }
+        }
+        self.spotlight_history.retain(|&x| Some(x) != id);
+
+        // A memo is only ever shown in one slot
+        for other in &mut self.spotlight_slots {
+            if other.memo.is_some() && other.memo == id {
+                other.memo = None;
+                other.last_update = None;
             }
+        }
+        self.spotlight_slots[slot] = SpotlightSlot {
+            memo: id,
+            last_update: Some(Instant::now()),
         };
+    }
 
-        if should_update {
-            self.current_spotlight_memo = self.get_random_cold_memo_id();
-            self.last_spotlight_update = Some(now);
+    // Goes back to the previous spotlight memo in a slot; the current one is
+    // shown again on the next skip
+    pub fn spotlight_back(&mut self, slot: usize) {
+        if self.is_spotlight_editing(slot) {
+            return;
         }
+        let Some(previous) = self.spotlight_history().first().copied() else {
+            return;
+        };
+        let Some(current) = self.spotlight_slots.get(slot).map(|slot| slot.memo) else {
+            return;
+        };
+        self.spotlight_history.retain(|&x| x != previous);
+        if let Some(current) = current {
+            self.spotlight_queue.insert(0, current);
+        }
+        self.spotlight_slots[slot] = SpotlightSlot {
+            memo: Some(previous),
+            last_update: Some(Instant::now()),
+        };
     }
 
-    pub fn get_last_spotlight_update(&self) -> Option<std::time::Instant> {
-        self.last_spotlight_update
+    // Previous spotlight memos that are still cold and not shown in another
+    // slot, most recent first
+    pub fn spotlight_history(&self) -> Vec<i32> {
+        let shown = self.spotlight_memos();
+        self.spotlight_history
+            .iter()
+            .rev()
+            .copied()
+            .filter(|id| {
+                !shown.contains(id)
+                    && self
+                        .memos
+                        .get(id)
+                        .is_some_and(|memo| memo.status == MemoStatus::Cold)
+            })
+            .collect()
     }
 
-    pub fn is_spotlight_paused(&self) -> bool {
-        self.config.pause_spotlight_when_expanded
-            && self.current_spotlight_memo.is_some()
-            && self
-                .spotlight_expanded_states
-                .get(&self.current_spotlight_memo.unwrap())
+    // "Not interested": keeps a memo out of the spotlight for a while
+    pub fn hide_from_spotlight(&mut self, id: i32) -> Result<()> {
+        let until = Utc::now() + chrono::Duration::days(SPOTLIGHT_HIDE_DAYS);
+        self.mutate(Some("Hide from spotlight"), |staged| {
+            staged.set_spotlight_hidden(id, Some(until))
+        })?;
+        if let Some(slot) = self
+            .spotlight_slots
+            .iter()
+            .position(|slot| slot.memo == Some(id))
+        {
+            self.rotate_spotlight(slot);
+        }
+        if let Some(memo) = self.memos.get(&id) {
+            let message = format!(
+                "Hidden from the spotlight for {} days: {}",
+                SPOTLIGHT_HIDE_DAYS, memo.title
+            );
+            self.show_undo_toast(message);
+        }
+        Ok(())
+    }
+
+    // Takes the first queued memo that can be shown, dropping ones that are
+    // no longer cold or already shown. Memos hidden by the tag filter wait in
+    // the queue.
+    fn next_queued_spotlight(&mut self) -> Option<i32> {
+        let memos = &self.memos;
+        let shown = self.spotlight_memos();
+        self.spotlight_queue.retain(|id| {
+            !shown.contains(id)
+                && memos
+                    .get(id)
+                    .is_some_and(|memo| memo.status == MemoStatus::Cold)
+        });
+        let pos = self.spotlight_queue.iter().position(|id| {
+            self.memos
+                .get(id)
+                .is_some_and(|memo| self.matches_tag_filter(memo))
+        })?;
+        Some(self.spotlight_queue.remove(pos))
+    }
+
+    pub fn get_last_spotlight_update(&self, slot: usize) -> Option<std::time::Instant> {
+        self.spotlight_slots
+            .get(slot)
+            .and_then(|slot| slot.last_update)
+    }
+
+    // A slot pauses while its memo is being edited, or expanded if so configured
+    pub fn is_spotlight_paused(&self, slot: usize) -> bool {
+        let expanded = || {
+            self.spotlight_slots
+                .get(slot)
+                .and_then(|slot| slot.memo)
+                .and_then(|id| self.spotlight_expanded_states.get(&id))
                 .copied()
                 .unwrap_or(false)
+        };
+        self.is_spotlight_editing(slot) || (self.config.pause_spotlight_when_expanded && expanded())
+    }
+
+    // The slot's memo is open in the inline editor, so the slot must not
+    // change under it
+    pub fn is_spotlight_editing(&self, slot: usize) -> bool {
+        self.editing_memo.is_some()
+            && self.spotlight_slots.get(slot).and_then(|slot| slot.memo) == self.editing_memo
     }
 
     pub fn validate_memo_input_height(&mut self, available_height: f32) {
@@ -362,43 +1188,301 @@ impl MemoApp {
         }
     }
 
-    fn get_random_cold_memo_id(&self) -> Option<i32> {
-        let cold_memo_ids: Vec<i32> = self
+    // Picks the next cold spotlight memo using the configured strategy from
+    // the memos not already in a spotlight slot
+    pub fn choose_spotlight_memo_id(&self) -> Option<i32> {
+        let shown = self.spotlight_memos();
+        let now = Utc::now();
+        let candidates: Vec<&MemoData> = self
+            .memos
+            .values()
+            .filter(|memo| {
+                memo.status == MemoStatus::Cold
+                    && !shown.contains(&memo.id)
+                    && self.matches_tag_filter(memo)
+                    && memo.spotlight_hidden_until.is_none_or(|until| until <= now)
+            })
+            .collect();
+
+        let last_shown = |memo: &MemoData| memo.last_spotlighted_at.unwrap_or(memo.creation_date);
+        let mut rng = rand::rng();
+        let chosen = match self.config.spotlight_strategy {
+            SpotlightStrategy::Uniform => candidates.choose(&mut rng),
+            SpotlightStrategy::LeastRecentlySpotlighted => candidates
+                .choose_weighted(&mut rng, |memo| {
+                    (now - last_shown(memo)).num_minutes().max(1) as f64
+                })
+                .ok(),
+            SpotlightStrategy::OldestFirst => candidates
+                .iter()
+                .min_by_key(|memo| (memo.spotlight_count, memo.creation_date)),
+            SpotlightStrategy::SpacedRepetition => {
+                // Each showing doubles the wait before the next one
+                candidates
+                    .iter()
+                    .min_by_key(|memo| match memo.last_spotlighted_at {
+                        Some(at) => {
+                            let doublings = memo.spotlight_count.saturating_sub(1).min(10);
+                            at + chrono::Duration::hours(SPACED_SPOTLIGHT_HOURS << doublings)
+                        }
+                        None => memo.creation_date,
+                    })
+            }
+        };
+        chosen.map(|memo| memo.id)
+    }
+
+    // Time left before an idle hot memo decays to cold; None if it never does
+    pub fn time_until_decay(&self, memo: &MemoData) -> Option<chrono::Duration> {
+        if self.config.hot_decay_hours == 0 || memo.status != MemoStatus::Hot || memo.pinned {
+            return None;
+        }
+        let decays_at =
+            memo.last_touched + chrono::Duration::hours(self.config.hot_decay_hours as i64);
+        Some(decays_at - Utc::now())
+    }
+
+    // Hot memos that have been idle for longer than `hot_decay_hours`
+    fn idle_memos(&self, hot_stack: &[i32], memos: &HashMap<i32, MemoData>) -> Vec<i32> {
+        let idle: Vec<i32> = hot_stack
+            .iter()
+            .copied()
+            .filter(|id| {
+                memos
+                    .get(id)
+                    .and_then(|memo| self.time_until_decay(memo))
+                    .is_some_and(|left| left <= chrono::Duration::zero())
+            })
+            .collect();
+        for id in &idle {
+            if let Some(memo) = memos.get(id) {
+                eprintln!(
+                    "Moving memo {} ({}) to cold: untouched since {} (hot_decay_hours is {})",
+                    id,
+                    memo.title,
+                    memo.last_touched.format("%Y-%m-%d %H:%M"),
+                    self.config.hot_decay_hours
+                );
+            }
+        }
+        idle
+    }
+
+    pub fn check_hot_decay(&mut self) -> Result<()> {
+        let decayed = self.idle_memos(&self.hot_stack, &self.memos);
+        if decayed.is_empty() {
+            return Ok(());
+        }
+
+        // Automatic, so not recorded for undo (undoing would restore the old
+        // last-touched time and the memo would decay again right away)
+        self.mutate(None, |staged| {
+            for id in &decayed {
+                staged.set_cold(*id)?;
+            }
+            Ok(())
+        })?;
+
+        let message = match decayed.as_slice() {
+            [id] => format!(
+                "Moved idle memo to cold: {}",
+                self.memos
+                    .get(id)
+                    .map(|m| m.title.as_str())
+                    .unwrap_or_default()
+            ),
+            _ => format!("Moved {} idle memos to cold", decayed.len()),
+        };
+        self.show_notice(message);
+        Ok(())
+    }
+
+    // Delayed memos that came due before the app started wait in the catch-up
+    // dialog instead of all waking (and evicting each other) at once. Ones
+    // that came due since then wake as usual, in any workspace.
+    fn collect_catch_up(&mut self) {
+        let started_at = self.started_at;
+        let mut overdue: Vec<&MemoData> = self
             .memos
+            .values()
+            .filter(|memo| {
+                memo.status == MemoStatus::Delayed
+                    && memo.wake_at.is_some_and(|at| at <= started_at)
+            })
+            .collect();
+        overdue.sort_by_key(|memo| memo.wake_at);
+        self.catch_up = overdue.iter().map(|memo| memo.id).collect();
+        self.catch_up_selected = self.catch_up.iter().copied().collect();
+    }
+
+    // Applies a catch-up dialog action to the selected memos
+    pub fn resolve_catch_up(&mut self, action: CatchUpAction) -> Result<()> {
+        let ids: Vec<i32> = self
+            .catch_up
             .iter()
-            .filter(|(_, memo)| memo.status == MemoStatus::Cold)
-            .map(|(&id, _)| id)
+            .copied()
+            .filter(|id| self.catch_up_selected.contains(id))
             .collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut evicted = Vec::new();
+        let verb = match action {
+            CatchUpAction::Wake => {
+                evicted = self.wake_memos(&ids, Some("Wake delayed memos"))?;
+                "Woke"
+            }
+            CatchUpAction::Snooze(until) => {
+                self.mutate(Some("Snooze"), |staged| {
+                    for &id in &ids {
+                        staged.set_delayed(id, until)?;
+                    }
+                    Ok(())
+                })?;
+                "Snoozed"
+            }
+            CatchUpAction::Cold => {
+                self.mutate(Some("Move to cold"), |staged| {
+                    for &id in &ids {
+                        staged.set_cold(id)?;
+                    }
+                    Ok(())
+                })?;
+                "Moved to cold"
+            }
+            CatchUpAction::Done => {
+                self.mutate(Some("Move to done"), |staged| {
+                    for &id in &ids {
+                        staged.set_done(id)?;
+                    }
+                    Ok(())
+                })?;
+                "Moved to done"
+            }
+        };
 
-        cold_memo_ids.choose(&mut rand::rng()).copied()
+        self.catch_up.retain(|id| !ids.contains(id));
+        self.catch_up_selected.retain(|id| !ids.contains(id));
+        let title = |id: &i32| {
+            self.memos
+                .get(id)
+                .map(|m| m.title.as_str())
+                .unwrap_or_default()
+        };
+        let mut message = match ids.as_slice() {
+            [id] => format!("{}: {}", verb, title(id)),
+            _ => format!("{} {} memos", verb, ids.len()),
+        };
+        match evicted.as_slice() {
+            [] => {}
+            [id] => message.push_str(&format!("; moved to cold: {}", title(id))),
+            _ => message.push_str(&format!("; moved {} memos to cold", evicted.len())),
+        }
+        self.show_undo_toast(message);
+        Ok(())
     }
 
     pub fn check_and_promote_delayed_memos(&mut self) -> Result<()> {
+        // Memos handled elsewhere (e.g. moved from the Delayed tab) leave the catch-up list
+        let memos = &self.memos;
+        self.catch_up.retain(|id| {
+            memos
+                .get(id)
+                .is_some_and(|memo| memo.status == MemoStatus::Delayed)
+        });
+
         let now = Utc::now();
         let mut to_promote = Vec::new();
 
         // Find delayed memos that are ready to be promoted
         for (id, memo) in &self.memos {
-            if memo.status == MemoStatus::Delayed {
-                if let Some(delay_minutes) = memo.delay_minutes {
-                    let promotion_time =
-                        memo.creation_date + chrono::Duration::minutes(delay_minutes as i64);
-
-                    if now >= promotion_time {
-                        to_promote.push(*id);
-                    }
-                }
+            if memo.status == MemoStatus::Delayed
+                && let Some(wake_at) = memo.wake_at
+                && now >= wake_at
+                && !self.catch_up.contains(id)
+            {
+                to_promote.push(*id);
             }
         }
 
-        // Promote memos to hot
-        for id in to_promote {
-            self.move_to_hot(id)?;
+        // Automatic, so not recorded for undo
+        if !to_promote.is_empty() {
+            let evicted = self.wake_memos(&to_promote, None)?;
+            self.report_evicted(&evicted);
         }
 
         Ok(())
     }
 
+    // Moves delayed memos to hot, cold or the spotlight queue as each asks;
+    // returns the memos moved to cold to make room. With the ask-the-user
+    // policy the eviction dialog does that.
+    fn wake_memos(&mut self, ids: &[i32], label: Option<&str>) -> Result<Vec<i32>> {
+        let spotlight: Vec<i32> = ids
+            .iter()
+            .copied()
+            .filter(|id| {
+                self.memos
+                    .get(id)
+                    .is_some_and(|memo| memo.wake_target == WakeTarget::Spotlight)
+            })
+            .collect();
+        let evicted = self.mutate(label, |staged| staged.wake_memos(ids))?;
+        self.spotlight_queue.extend(spotlight);
+        Ok(evicted)
+    }
+
+    // Wakes delayed memos and decays idle ones in the workspaces that are not
+    // shown, so they don't pile up until the user switches there. Memos that
+    // came due before the app started are left for the catch-up dialog.
+    pub fn check_background_workspaces(&mut self) -> Result<()> {
+        if self
+            .background_checked_at
+            .is_some_and(|at| at.elapsed() < BACKGROUND_WORKSPACE_INTERVAL)
+        {
+            return Ok(());
+        }
+        self.background_checked_at = Some(Instant::now());
+
+        let now = Utc::now();
+        let tx = self.db.unchecked_transaction()?;
+        for workspace in &self.workspaces {
+            if workspace.id == self.workspace_id {
+                continue;
+            }
+            let (hot_stack, memos) = database::load_state(&tx, workspace.id)?;
+            let due: Vec<i32> = memos
+                .values()
+                .filter(|memo| {
+                    memo.status == MemoStatus::Delayed
+                        && memo
+                            .wake_at
+                            .is_some_and(|at| at > self.started_at && at <= now)
+                })
+                .map(|memo| memo.id)
+                .collect();
+            let idle = self.idle_memos(&hot_stack, &memos);
+            if due.is_empty() && idle.is_empty() {
+                continue;
+            }
+
+            let mut staged = StagedState {
+                db: &tx,
+                config: &self.config,
+                workspace_id: workspace.id,
+                max_hot_count: workspace.max_hot_count.unwrap_or(self.config.max_hot_count),
+                hot_stack,
+                memos,
+            };
+            for id in idle {
+                staged.set_cold(id)?;
+            }
+            staged.wake_memos(&due)?;
+        }
+        tx.commit()
+    }
+
     // Helper method to indent or outdent selected lines
     // Helper method to indent or outdent selected lines - simplified approach
     pub fn handle_tab_indent(&mut self, cursor_pos: usize, is_indent: bool) {
@@ -623,16 +1707,57 @@ impl eframe::App for MemoApp {
             let _ = self.save_app_state();
         }
 
+        // Undo/redo shortcuts (text fields keep their own undo)
+        if !ctx.wants_keyboard_input() {
+            let (redo, undo) = ctx.input_mut(|i| {
+                let redo = i.consume_key(
+                    egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                    egui::Key::Z,
+                );
+                let undo = !redo && i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                (redo, undo)
+            });
+            if redo && let Err(e) = self.redo() {
+                eprintln!("Error redoing: {}", e);
+            }
+            if undo && let Err(e) = self.undo() {
+                eprintln!("Error undoing: {}", e);
+            }
+        }
+
+        // Pick up changes other processes made to the database
+        if let Err(e) = self.check_external_changes() {
+            eprintln!("Error reloading memos: {}", e);
+        }
+
+        // Run commands forwarded from the command line
+        self.process_ipc_requests();
+
         // Check for delayed memos that should be promoted
         if let Err(e) = self.check_and_promote_delayed_memos() {
             eprintln!("Error promoting delayed memos: {}", e);
         }
 
+        // Move hot memos nobody has touched in a while to cold
+        if let Err(e) = self.check_hot_decay() {
+            eprintln!("Error moving idle memos to cold: {}", e);
+        }
+
+        // The same for the workspaces that are not shown
+        if let Err(e) = self.check_background_workspaces() {
+            eprintln!("Error updating other workspaces: {}", e);
+        }
+
         self.render_ui(ctx, frame);
     }
 
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         // Save app state on shutdown
         let _ = self.save_app_state();
+
+        // Remove the command socket so the CLI falls back to the database
+        if self.ipc_requests.is_some() {
+            let _ = fs::remove_file(ipc::socket_path());
+        }
     }
 }
@@ -0,0 +1,517 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+use crate::app::MemoApp;
+use crate::ipc;
+use crate::models::{MemoData, MemoStatus, WakeTarget};
+use crate::recurrence::Recurrence;
+use crate::schedule::parse_wake_time;
+
+const USAGE: &str = "\
+Usage: memo-stack [--json] [--workspace <name>] [COMMAND]
+
+Without a command, opens the memo-stack window.
+
+Commands:
+  add [--delay <when>] [--] <text>... Add a memo (use '-' to read text from stdin)
+  list [--status <status>]            List memos (hot, cold, done, delayed or all; default hot)
+  done <id>                           Move a memo to done
+  hot <id>                            Move a memo to the top of the hot stack
+  cold <id>                           Move a memo to cold
+  delay <id> <when>... [--into <to>]  Delay a memo, e.g. 2h, 1h30m, in 3 days, tomorrow 9am,
+                                      fri 14:00 or 2026-11-02 08:30; it wakes into hot, cold
+                                      or spotlight (default: unchanged, initially hot)
+  repeat <id> <rule>...               Repeat a memo when it is done, e.g. daily, weekdays 8:30,
+                                      weekly mon,thu 14:00, monthly 1, cron 0 9 * * 1 or off
+  spotlight                           Show a cold memo picked by the spotlight strategy
+  help                                Show this message
+
+Options (before the command):
+  --json                              Print output as JSON
+  --workspace <name>                  Work on the named workspace (default: the one open last)";
+
+// Delays are resolved to absolute times when parsing, so a command means
+// the same thing whether it runs here or is forwarded to the running window
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Command {
+    Add {
+        text: String,
+        wake_at: Option<DateTime<Utc>>,
+    },
+    List {
+        status: Option<MemoStatus>,
+    },
+    Done {
+        id: i32,
+    },
+    Hot {
+        id: i32,
+    },
+    Cold {
+        id: i32,
+    },
+    Delay {
+        id: i32,
+        until: DateTime<Utc>,
+        target: Option<WakeTarget>,
+    },
+    Repeat {
+        id: i32,
+        recurrence: Option<Recurrence>, // None stops repeating
+    },
+    Spotlight,
+    Help,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invocation {
+    pub command: Command,
+    pub json: bool,
+    #[serde(default)]
+    pub workspace: Option<String>, // None = the workspace open last
+}
+
+// Entry point for `memo-stack <command>`; returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let invocation = match parse_args(args) {
+        Ok(invocation) => invocation,
+        Err(e) => {
+            eprintln!("Error: {}\n\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    if invocation.command == Command::Help {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    // Prefer the running window so the change shows up there immediately.
+    // It only runs commands on the workspace it shows, so commands for a
+    // named workspace work on the database; the window picks them up from there.
+    if invocation.workspace.is_none()
+        && let Some(result) = ipc::send(&invocation)
+    {
+        return print_result(result);
+    }
+
+    let mut app = match MemoApp::new() {
+        Ok(app) => app,
+        Err(e) => {
+            eprintln!("Error opening database: {}", e);
+            return 1;
+        }
+    };
+
+    if let Some(name) = &invocation.workspace {
+        let Some(id) = app
+            .workspaces
+            .iter()
+            .find(|w| &w.name == name)
+            .map(|w| w.id)
+        else {
+            eprintln!("Error: no workspace named '{}'", name);
+            return 1;
+        };
+        if let Err(e) = app.open_workspace(id) {
+            eprintln!("Error opening workspace: {}", e);
+            return 1;
+        }
+    }
+
+    print_result(execute(&mut app, &invocation))
+}
+
+fn print_result(result: Result<String, String>) -> i32 {
+    match result {
+        Ok(output) => {
+            // Ignore broken pipes (e.g. `memo-stack list | head`)
+            if !output.is_empty() {
+                let _ = writeln!(std::io::stdout(), "{}", output);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Invocation, String> {
+    let mut args = args.iter().cloned().peekable();
+
+    // Options that apply to every command come before it, so memo text can
+    // contain words like "--json"
+    let mut json = false;
+    let mut workspace = None;
+    while let Some(option) = args.next_if(|arg| arg.starts_with("--") && arg != "--help") {
+        match option.as_str() {
+            "--json" => json = true,
+            "--workspace" => workspace = Some(args.next().ok_or("--workspace needs a name")?),
+            other => return Err(format!("unknown option '{}'", other)),
+        }
+    }
+
+    let command = match args.next().as_deref() {
+        None | Some("help") | Some("--help") | Some("-h") => Command::Help,
+        Some("add") => {
+            let mut wake_at = None;
+            let mut words = Vec::new();
+            while let Some(arg) = args.next() {
+                if arg == "--" {
+                    // The rest is text, even if it looks like an option
+                    words.extend(args.by_ref());
+                } else if arg == "--delay" {
+                    let value = args.next().ok_or("--delay needs a time")?;
+                    wake_at = Some(parse_wake_time(&value, Local::now())?);
+                } else {
+                    words.push(arg);
+                }
+            }
+
+            let text = if words.len() == 1 && words[0] == "-" {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| format!("could not read stdin: {}", e))?;
+                text
+            } else {
+                words.join(" ")
+            };
+
+            if text.trim().is_empty() {
+                return Err("memo text is empty".to_string());
+            }
+            Command::Add { text, wake_at }
+        }
+        Some("list") => {
+            let mut status = Some(MemoStatus::Hot);
+            while let Some(arg) = args.next() {
+                match arg.as_str() {
+                    "--status" => {
+                        let value = args.next().ok_or("--status needs a value")?;
+                        status = parse_status(&value)?;
+                    }
+                    other => return Err(format!("unexpected argument '{}'", other)),
+                }
+            }
+            Command::List { status }
+        }
+        Some("done") => Command::Done {
+            id: parse_id(args.next())?,
+        },
+        Some("hot") => Command::Hot {
+            id: parse_id(args.next())?,
+        },
+        Some("cold") => Command::Cold {
+            id: parse_id(args.next())?,
+        },
+        Some("delay") => {
+            let id = parse_id(args.next())?;
+            // The rest of the arguments, so "tomorrow 9am" needs no quotes
+            let mut target = None;
+            let mut words = Vec::new();
+            while let Some(arg) = args.next() {
+                if arg == "--into" {
+                    let value = args.next().ok_or("--into needs hot, cold or spotlight")?;
+                    target = Some(parse_wake_target(&value)?);
+                } else {
+                    words.push(arg);
+                }
+            }
+            let value = words.join(" ");
+            if value.is_empty() {
+                return Err("delay needs a time".to_string());
+            }
+            Command::Delay {
+                id,
+                until: parse_wake_time(&value, Local::now())?,
+                target,
+            }
+        }
+        Some("repeat") => {
+            let id = parse_id(args.next())?;
+            let value = args.by_ref().collect::<Vec<_>>().join(" ");
+            let recurrence = match value.as_str() {
+                "" => return Err("repeat needs a rule".to_string()),
+                "off" | "never" => None,
+                rule => Some(Recurrence::parse(rule)?),
+            };
+            Command::Repeat { id, recurrence }
+        }
+        Some("spotlight") => Command::Spotlight,
+        Some(other) => return Err(format!("unknown command '{}'", other)),
+    };
+
+    if let Some(extra) = args.next()
+        && !matches!(command, Command::Add { .. } | Command::List { .. })
+    {
+        return Err(format!("unexpected argument '{}'", extra));
+    }
+
+    Ok(Invocation {
+        command,
+        json,
+        workspace,
+    })
+}
+
+// Runs a parsed command against the app state and returns the text to print
+pub fn execute(app: &mut MemoApp, invocation: &Invocation) -> Result<String, String> {
+    let json = invocation.json;
+    match &invocation.command {
+        Command::Help => Ok(USAGE.to_string()),
+        Command::Add { text, wake_at } => {
+            let (title, body) = MemoApp::parse_memo_text(text);
+            let id = app
+                .add_memo(title, body, *wake_at)
+                .map_err(|e| e.to_string())?;
+            format_memo_result(app, id, json, "Added")
+        }
+        Command::List { status } => {
+            let memos = list_memos(app, *status);
+            if json {
+                serde_json::to_string_pretty(&memos).map_err(|e| e.to_string())
+            } else {
+                Ok(memos
+                    .iter()
+                    .map(format_memo_line)
+                    .collect::<Vec<_>>()
+                    .join("\n"))
+            }
+        }
+        Command::Done { id } => {
+            require_memo(app, *id)?;
+            app.move_to_done(*id).map_err(|e| e.to_string())?;
+            format_memo_result(app, *id, json, "Moved to done")
+        }
+        Command::Hot { id } => {
+            require_memo(app, *id)?;
+            app.move_to_hot(*id).map_err(|e| e.to_string())?;
+            format_memo_result(app, *id, json, "Moved to hot")
+        }
+        Command::Cold { id } => {
+            require_memo(app, *id)?;
+            app.move_to_cold(*id).map_err(|e| e.to_string())?;
+            format_memo_result(app, *id, json, "Moved to cold")
+        }
+        Command::Delay { id, until, target } => {
+            require_memo(app, *id)?;
+            app.delay_memo(*id, *until, *target)
+                .map_err(|e| e.to_string())?;
+            format_memo_result(app, *id, json, "Delayed")
+        }
+        Command::Repeat { id, recurrence } => {
+            require_memo(app, *id)?;
+            app.set_recurrence(*id, recurrence.clone())
+                .map_err(|e| e.to_string())?;
+            let action = match recurrence {
+                Some(rule) => format!("Repeats {}", rule),
+                None => "Stopped repeating".to_string(),
+            };
+            format_memo_result(app, *id, json, &action)
+        }
+        Command::Spotlight => match app.choose_spotlight_memo_id() {
+            Some(id) => format_memo_result(app, id, json, "Spotlight"),
+            None if json => Ok("null".to_string()),
+            None => Ok("No cold memos".to_string()),
+        },
+    }
+}
+
+fn list_memos(app: &mut MemoApp, status: Option<MemoStatus>) -> Vec<MemoData> {
+    let statuses = match status {
+        Some(status) => vec![status],
+        None => vec![
+            MemoStatus::Hot,
+            MemoStatus::Delayed,
+            MemoStatus::Cold,
+            MemoStatus::Done,
+        ],
+    };
+
+    let mut memos = Vec::new();
+    for status in statuses {
+        match status {
+            // Hot memos in stack order
+            MemoStatus::Hot => memos.extend(
+                app.hot_stack
+                    .iter()
+                    .filter_map(|id| app.memos.get(id))
+                    .cloned(),
+            ),
+            _ => memos.extend(
+                app.get_filtered_memos(status, "")
+                    .into_iter()
+                    .map(|(_, memo)| memo),
+            ),
+        }
+    }
+    memos
+}
+
+fn require_memo(app: &MemoApp, id: i32) -> Result<(), String> {
+    if app.memos.contains_key(&id) {
+        Ok(())
+    } else {
+        Err(format!("no memo with id {}", id))
+    }
+}
+
+fn format_memo_result(app: &MemoApp, id: i32, json: bool, action: &str) -> Result<String, String> {
+    let memo = app
+        .memos
+        .get(&id)
+        .ok_or_else(|| format!("no memo with id {}", id))?;
+    if json {
+        serde_json::to_string_pretty(memo).map_err(|e| e.to_string())
+    } else {
+        Ok(format!("{}: {}", action, format_memo_line(memo)))
+    }
+}
+
+fn format_memo_line(memo: &MemoData) -> String {
+    format!("{}\t{}\t{}", memo.id, memo.status.as_str(), memo.title)
+}
+
+fn parse_id(value: Option<String>) -> Result<i32, String> {
+    let value = value.ok_or("missing memo id")?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid memo id '{}'", value))
+}
+
+fn parse_wake_target(value: &str) -> Result<WakeTarget, String> {
+    match value {
+        "hot" => Ok(WakeTarget::Hot),
+        "cold" => Ok(WakeTarget::Cold),
+        "spotlight" => Ok(WakeTarget::Spotlight),
+        other => Err(format!("unknown wake target '{}'", other)),
+    }
+}
+
+fn parse_status(value: &str) -> Result<Option<MemoStatus>, String> {
+    match value {
+        "hot" => Ok(Some(MemoStatus::Hot)),
+        "cold" => Ok(Some(MemoStatus::Cold)),
+        "done" => Ok(Some(MemoStatus::Done)),
+        "delayed" => Ok(Some(MemoStatus::Delayed)),
+        "all" => Ok(None),
+        other => Err(format!("unknown status '{}'", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(line: &str) -> Result<Invocation, String> {
+        let args: Vec<String> = line.split_whitespace().map(String::from).collect();
+        parse_args(&args)
+    }
+
+    fn add(text: &str) -> Command {
+        Command::Add {
+            text: text.to_string(),
+            wake_at: None,
+        }
+    }
+
+    #[test]
+    fn parses_options_before_the_command() {
+        assert_eq!(
+            parse("--json --workspace home list --status cold"),
+            Ok(Invocation {
+                command: Command::List {
+                    status: Some(MemoStatus::Cold)
+                },
+                json: true,
+                workspace: Some("home".to_string()),
+            })
+        );
+        assert_eq!(
+            parse("--verbose list"),
+            Err("unknown option '--verbose'".to_string())
+        );
+        assert_eq!(
+            parse("--workspace"),
+            Err("--workspace needs a name".to_string())
+        );
+    }
+
+    #[test]
+    fn keeps_option_like_words_in_memo_text() {
+        let invocation = parse("add fix the --json output").unwrap();
+        assert_eq!(invocation.command, add("fix the --json output"));
+        assert!(!invocation.json);
+
+        let invocation = parse("add move to --workspace later").unwrap();
+        assert_eq!(invocation.command, add("move to --workspace later"));
+        assert_eq!(invocation.workspace, None);
+
+        // Everything after `--` is text
+        assert_eq!(
+            parse("add -- --delay is a flag").unwrap().command,
+            add("--delay is a flag")
+        );
+    }
+
+    #[test]
+    fn parses_add_with_delay() {
+        let Command::Add { text, wake_at } = parse("add --delay 2h call back").unwrap().command
+        else {
+            panic!("expected add");
+        };
+        assert_eq!(text, "call back");
+        assert!(wake_at.is_some_and(|at| at > Utc::now()));
+        assert!(parse("add --delay").is_err());
+        assert_eq!(parse("add"), Err("memo text is empty".to_string()));
+    }
+
+    #[test]
+    fn parses_memo_commands() {
+        assert_eq!(parse("").unwrap().command, Command::Help);
+        assert_eq!(parse("--help").unwrap().command, Command::Help);
+        assert_eq!(parse("done 3").unwrap().command, Command::Done { id: 3 });
+        assert_eq!(
+            parse("repeat 3 off").unwrap().command,
+            Command::Repeat {
+                id: 3,
+                recurrence: None
+            }
+        );
+        assert_eq!(
+            parse("list --status all").unwrap().command,
+            Command::List { status: None }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert_eq!(parse("done x"), Err("invalid memo id 'x'".to_string()));
+        assert_eq!(parse("done"), Err("missing memo id".to_string()));
+        assert_eq!(
+            parse("done 3 4"),
+            Err("unexpected argument '4'".to_string())
+        );
+        // Options go before the command
+        assert_eq!(
+            parse("list --json"),
+            Err("unexpected argument '--json'".to_string())
+        );
+        assert_eq!(
+            parse("done 3 --json"),
+            Err("unexpected argument '--json'".to_string())
+        );
+        assert_eq!(
+            parse("list --status warm"),
+            Err("unknown status 'warm'".to_string())
+        );
+        assert_eq!(
+            parse("frobnicate"),
+            Err("unknown command 'frobnicate'".to_string())
+        );
+    }
+}
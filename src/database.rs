@@ -1,91 +1,70 @@
 use chrono::{DateTime, Utc};
 use rusqlite::{Connection, Result};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use crate::models::{MemoData, MemoStatus};
+use crate::migrations;
+use crate::models::{
+    AppState, MemoData, MemoRevision, MemoStatus, SearchHit, WakeTarget, Workspace,
+};
+use crate::recurrence::Recurrence;
+use crate::review::ReviewSchedule;
+use crate::tags;
+
+// Markers around matched terms in search titles and snippets
+pub const MATCH_START: char = '\u{2}';
+pub const MATCH_END: char = '\u{3}';
 
 pub fn create_tables(db: &Connection) -> Result<()> {
-    // Create tables
-    db.execute(
-        "CREATE TABLE IF NOT EXISTS memos (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            title TEXT NOT NULL,
-            body TEXT NOT NULL,
-            status TEXT NOT NULL DEFAULT 'hot',
-            creation_date TEXT NOT NULL,
-            moved_to_done_date TEXT,
-            delay_minutes INTEGER
-        )",
-        [],
-    )?;
+    migrations::run(db)
+}
 
-    db.execute(
-        "CREATE TABLE IF NOT EXISTS hot_stack_state (
-            id INTEGER PRIMARY KEY DEFAULT 1,
-            stack_json TEXT NOT NULL DEFAULT '[]'
-        )",
-        [],
-    )?;
+// Changes whenever another connection commits to the database
+pub fn data_version(db: &Connection) -> Result<i64> {
+    db.query_row("PRAGMA data_version", [], |row| row.get(0))
+}
 
-    db.execute(
-        "INSERT OR IGNORE INTO hot_stack_state (id, stack_json) VALUES (1, '[]')",
-        [],
-    )?;
+// Loads the memos and hot stack of one workspace
+pub fn load_state(
+    db: &Connection,
+    workspace_id: i32,
+) -> Result<(Vec<i32>, HashMap<i32, MemoData>)> {
+    let memos = load_memos(db, "workspace_id = ?1", workspace_id)?;
 
-    // Create app_state table
-    db.execute(
-        "CREATE TABLE IF NOT EXISTS app_state (
-            id INTEGER PRIMARY KEY DEFAULT 1,
-            memo_input_height REAL NOT NULL DEFAULT 80.0,
-            always_on_top INTEGER NOT NULL DEFAULT 0,
-            new_memo_text TEXT NOT NULL DEFAULT '',
-            window_width REAL NOT NULL DEFAULT 800.0,
-            window_height REAL NOT NULL DEFAULT 600.0,
-            window_x REAL,
-            window_y REAL
-        )",
-        [],
+    // Load hot stack order. Hot memos without a position (e.g. inserted by
+    // another tool) are treated as newest and go on top.
+    let mut stmt = db.prepare(
+        "SELECT id FROM memos WHERE status = 'hot' AND workspace_id = ?1
+         ORDER BY stack_position IS NOT NULL, stack_position, id DESC",
     )?;
+    let hot_stack = stmt
+        .query_map([workspace_id], |row| row.get(0))?
+        .collect::<Result<Vec<i32>>>()?;
 
-    db.execute("INSERT OR IGNORE INTO app_state (id) VALUES (1)", [])?;
-
-    // Add delay_minutes column if it doesn't exist (migration)
-    let _ = db.execute("ALTER TABLE memos ADD COLUMN delay_minutes INTEGER", []);
-
-    // Add window position/size columns if they don't exist (migration)
-    let _ = db.execute(
-        "ALTER TABLE app_state ADD COLUMN window_width REAL NOT NULL DEFAULT 800.0",
-        [],
-    );
-    let _ = db.execute(
-        "ALTER TABLE app_state ADD COLUMN window_height REAL NOT NULL DEFAULT 600.0",
-        [],
-    );
-    let _ = db.execute("ALTER TABLE app_state ADD COLUMN window_x REAL", []);
-    let _ = db.execute("ALTER TABLE app_state ADD COLUMN window_y REAL", []);
-
-    Ok(())
+    Ok((hot_stack, memos))
 }
 
-pub fn load_state(db: &Connection) -> Result<(Vec<i32>, HashMap<i32, MemoData>)> {
-    // Load hot stack order
-    let stack_json: String = db.query_row(
-        "SELECT stack_json FROM hot_stack_state WHERE id = 1",
-        [],
-        |row| row.get(0),
-    )?;
-
-    let mut hot_stack: Vec<i32> = serde_json::from_str(&stack_json).unwrap_or_default();
+// Loads a single memo from any workspace
+pub fn load_memo(db: &Connection, id: i32) -> Result<Option<MemoData>> {
+    Ok(load_memos(db, "id = ?1", id)?.remove(&id))
+}
 
-    // Load all memos
+// Loads the memos matching `filter`, a condition on one parameter
+fn load_memos(db: &Connection, filter: &str, key: i32) -> Result<HashMap<i32, MemoData>> {
     let mut memos = HashMap::new();
-    let mut stmt =
-        db.prepare("SELECT id, title, body, status, creation_date, moved_to_done_date, delay_minutes FROM memos")?;
-    let memo_iter = stmt.query_map([], |row| {
+    let mut stmt = db.prepare(&format!(
+        "SELECT id, title, body, status, creation_date, moved_to_done_date, wake_at, pinned, last_touched, recurrence, wake_target, last_spotlighted_at, spotlight_count, review_due_at, review_interval_days, review_ease, spotlight_hidden_until, workspace_id FROM memos WHERE {}",
+        filter
+    ))?;
+    let memo_iter = stmt.query_map([key], |row| {
         let id: i32 = row.get(0)?;
         let creation_date_str: String = row.get(4)?;
         let moved_to_done_date_str: Option<String> = row.get(5)?;
-        let delay_minutes: Option<u32> = row.get::<_, Option<i32>>(6)?.map(|v| v as u32);
+        let wake_at_str: Option<String> = row.get(6)?;
+        let last_touched_str: Option<String> = row.get(8)?;
+        let recurrence_str: Option<String> = row.get(9)?;
+        let last_spotlighted_str: Option<String> = row.get(11)?;
+        let review_due_str: Option<String> = row.get(13)?;
+        let hidden_until_str: Option<String> = row.get(16)?;
 
         let creation_date = DateTime::parse_from_rfc3339(&creation_date_str)
             .unwrap_or_else(|_| Utc::now().into())
@@ -95,16 +74,59 @@ pub fn load_state(db: &Connection) -> Result<(Vec<i32>, HashMap<i32, MemoData>)>
             .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
             .map(|dt| dt.with_timezone(&Utc));
 
+        let wake_at = wake_at_str
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let last_touched = last_touched_str
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .unwrap_or(creation_date);
+
+        let last_spotlighted_at = last_spotlighted_str
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let spotlight_hidden_until = hidden_until_str
+            .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+
+        let review = ReviewSchedule {
+            due_at: review_due_str
+                .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+                .map(|dt| dt.with_timezone(&Utc)),
+            interval_days: row.get(14)?,
+            ease: row.get(15)?,
+        };
+
+        let recurrence = recurrence_str.and_then(|s| match Recurrence::parse(&s) {
+            Ok(rule) => Some(rule),
+            Err(e) => {
+                eprintln!("Ignoring repeat rule of memo {}: {}", id, e);
+                None
+            }
+        });
+
         Ok((
             id,
             MemoData {
                 id,
+                workspace_id: row.get(17)?,
                 title: row.get(1)?,
                 body: row.get(2)?,
                 status: MemoStatus::from_string(&row.get::<_, String>(3)?),
                 creation_date,
                 moved_to_done_date,
-                delay_minutes,
+                wake_at,
+                wake_target: WakeTarget::from_string(&row.get::<_, String>(10)?),
+                tags: Vec::new(),
+                pinned: row.get::<_, i32>(7)? != 0,
+                last_touched,
+                recurrence,
+                last_spotlighted_at,
+                spotlight_count: row.get(12)?,
+                spotlight_hidden_until,
+                review,
                 expanded: false,
             },
         ))
@@ -115,44 +137,52 @@ pub fn load_state(db: &Connection) -> Result<(Vec<i32>, HashMap<i32, MemoData>)>
         memos.insert(id, memo);
     }
 
-    // Clean up hot stack - remove any IDs that don't exist or aren't hot
-    hot_stack.retain(|id| {
-        memos
-            .get(id)
-            .map_or(false, |memo| memo.status == MemoStatus::Hot)
-    });
+    // Attach tags
+    let mut stmt = db.prepare(&format!(
+        "SELECT memo_id, tag FROM memo_tags
+         WHERE memo_id IN (SELECT id FROM memos WHERE {})
+         ORDER BY tag",
+        filter
+    ))?;
+    let tag_iter = stmt.query_map([key], |row| Ok((row.get::<_, i32>(0)?, row.get(1)?)))?;
+    for tag_result in tag_iter {
+        let (id, tag) = tag_result?;
+        if let Some(memo) = memos.get_mut(&id) {
+            memo.tags.push(tag);
+        }
+    }
 
-    Ok((hot_stack, memos))
+    Ok(memos)
 }
 
-pub fn save_hot_stack(db: &Connection, hot_stack: &[i32]) -> Result<()> {
-    let stack_json = serde_json::to_string(hot_stack).unwrap();
+// Rewrites a workspace's hot stack order. Multi-step changes (status + order)
+// are expected to run inside the caller's transaction.
+pub fn save_hot_stack(db: &Connection, workspace_id: i32, hot_stack: &[i32]) -> Result<()> {
     db.execute(
-        "UPDATE hot_stack_state SET stack_json = ?1 WHERE id = 1",
-        [stack_json],
+        "UPDATE memos SET stack_position = NULL WHERE stack_position IS NOT NULL AND workspace_id = ?1",
+        [workspace_id],
     )?;
+    let mut stmt = db.prepare("UPDATE memos SET stack_position = ?1 WHERE id = ?2")?;
+    for (position, id) in hot_stack.iter().enumerate() {
+        stmt.execute(rusqlite::params![position as i64, id])?;
+    }
     Ok(())
 }
 
 pub fn add_memo(
     db: &Connection,
+    workspace_id: i32,
     title: &str,
     body: &str,
-    delay_minutes: Option<u32>,
+    wake_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
 ) -> Result<i32> {
-    let now = Utc::now();
-
     // Insert memo into database
-    let status = if delay_minutes.is_some() {
-        "delayed"
-    } else {
-        "hot"
-    };
-    let delay_value = delay_minutes.map(|v| v as i32);
+    let status = if wake_at.is_some() { "delayed" } else { "hot" };
 
     db.execute(
-        "INSERT INTO memos (title, body, status, creation_date, delay_minutes) VALUES (?1, ?2, ?3, ?4, ?5)",
-        rusqlite::params![title, body, status, &now.to_rfc3339(), delay_value],
+        "INSERT INTO memos (title, body, status, creation_date, wake_at, last_touched, workspace_id) VALUES (?1, ?2, ?3, ?4, ?5, ?4, ?6)",
+        rusqlite::params![title, body, status, &now.to_rfc3339(), wake_at.map(|d| d.to_rfc3339()), workspace_id],
     )?;
 
     // Get the new memo ID
@@ -160,55 +190,348 @@ pub fn add_memo(
     Ok(new_id)
 }
 
+// Writes a full memo row, inserting it with its original id if it was deleted
+pub fn save_memo(db: &Connection, memo: &MemoData) -> Result<()> {
+    db.execute(
+        "INSERT INTO memos (id, title, body, status, creation_date, moved_to_done_date, wake_at, pinned, last_touched, recurrence, wake_target, last_spotlighted_at, spotlight_count,
+                            review_due_at, review_interval_days, review_ease, spotlight_hidden_until, workspace_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)
+         ON CONFLICT(id) DO UPDATE SET
+            title = excluded.title,
+            body = excluded.body,
+            status = excluded.status,
+            creation_date = excluded.creation_date,
+            moved_to_done_date = excluded.moved_to_done_date,
+            wake_at = excluded.wake_at,
+            pinned = excluded.pinned,
+            last_touched = excluded.last_touched,
+            recurrence = excluded.recurrence,
+            wake_target = excluded.wake_target,
+            last_spotlighted_at = excluded.last_spotlighted_at,
+            spotlight_count = excluded.spotlight_count,
+            review_due_at = excluded.review_due_at,
+            review_interval_days = excluded.review_interval_days,
+            review_ease = excluded.review_ease,
+            spotlight_hidden_until = excluded.spotlight_hidden_until,
+            workspace_id = excluded.workspace_id",
+        rusqlite::params![
+            memo.id,
+            memo.title,
+            memo.body,
+            memo.status.as_str(),
+            memo.creation_date.to_rfc3339(),
+            memo.moved_to_done_date.map(|d| d.to_rfc3339()),
+            memo.wake_at.map(|d| d.to_rfc3339()),
+            memo.pinned as i32,
+            memo.last_touched.to_rfc3339(),
+            memo.recurrence.as_ref().map(|rule| rule.to_string()),
+            memo.wake_target.as_str(),
+            memo.last_spotlighted_at.map(|d| d.to_rfc3339()),
+            memo.spotlight_count,
+            memo.review.due_at.map(|d| d.to_rfc3339()),
+            memo.review.interval_days,
+            memo.review.ease,
+            memo.spotlight_hidden_until.map(|d| d.to_rfc3339()),
+            memo.workspace_id
+        ],
+    )?;
+    save_memo_tags(db, memo.id, &memo.tags)?;
+    Ok(())
+}
+
+pub fn save_memo_tags(db: &Connection, id: i32, tags: &[String]) -> Result<()> {
+    db.execute("DELETE FROM memo_tags WHERE memo_id = ?1", [id])?;
+    let mut stmt = db.prepare("INSERT INTO memo_tags (memo_id, tag) VALUES (?1, ?2)")?;
+    for tag in tags {
+        stmt.execute(rusqlite::params![id, tag])?;
+    }
+    Ok(())
+}
+
+// Re-derives tags for memos whose text was changed by another program.
+// Returns whether anything was updated.
+pub fn sync_tags(db: &Connection, memos: &mut HashMap<i32, MemoData>) -> Result<bool> {
+    let mut changed = false;
+    for memo in memos.values_mut() {
+        let tags = tags::parse_tags(&memo.title, &memo.body);
+        if tags != memo.tags {
+            save_memo_tags(db, memo.id, &tags)?;
+            memo.tags = tags;
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
 pub fn update_memo_status(db: &Connection, id: i32, status: MemoStatus) -> Result<()> {
     match status {
         MemoStatus::Done => {
             let now = Utc::now();
             db.execute(
-                "UPDATE memos SET status = 'done', moved_to_done_date = ?1 WHERE id = ?2",
+                "UPDATE memos SET status = 'done', moved_to_done_date = ?1, stack_position = NULL, pinned = 0, wake_at = NULL WHERE id = ?2",
                 [&now.to_rfc3339(), &id.to_string()],
             )?;
         }
         MemoStatus::Hot => {
             db.execute(
-                "UPDATE memos SET status = 'hot', moved_to_done_date = NULL WHERE id = ?1",
+                "UPDATE memos SET status = 'hot', moved_to_done_date = NULL, wake_at = NULL WHERE id = ?1",
                 [id],
             )?;
         }
         MemoStatus::Cold => {
-            db.execute("UPDATE memos SET status = 'cold' WHERE id = ?1", [id])?;
+            db.execute(
+                "UPDATE memos SET status = 'cold', stack_position = NULL, pinned = 0, wake_at = NULL WHERE id = ?1",
+                [id],
+            )?;
         }
         MemoStatus::Delayed => {
-            db.execute("UPDATE memos SET status = 'delayed' WHERE id = ?1", [id])?;
+            db.execute(
+                "UPDATE memos SET status = 'delayed', stack_position = NULL, pinned = 0 WHERE id = ?1",
+                [id],
+            )?;
         }
     }
     Ok(())
 }
 
+pub fn update_memo_wake_at(db: &Connection, id: i32, wake_at: DateTime<Utc>) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET wake_at = ?1, moved_to_done_date = NULL WHERE id = ?2",
+        rusqlite::params![wake_at.to_rfc3339(), id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_pinned(db: &Connection, id: i32, pinned: bool) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET pinned = ?1 WHERE id = ?2",
+        rusqlite::params![pinned as i32, id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_recurrence(
+    db: &Connection,
+    id: i32,
+    recurrence: Option<&Recurrence>,
+) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET recurrence = ?1 WHERE id = ?2",
+        rusqlite::params![recurrence.map(|rule| rule.to_string()), id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_wake_target(db: &Connection, id: i32, target: WakeTarget) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET wake_target = ?1 WHERE id = ?2",
+        rusqlite::params![target.as_str(), id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_spotlighted(
+    db: &Connection,
+    id: i32,
+    at: DateTime<Utc>,
+    count: u32,
+) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET last_spotlighted_at = ?1, spotlight_count = ?2 WHERE id = ?3",
+        rusqlite::params![at.to_rfc3339(), count, id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_review(db: &Connection, id: i32, review: &ReviewSchedule) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET review_due_at = ?1, review_interval_days = ?2, review_ease = ?3 WHERE id = ?4",
+        rusqlite::params![
+            review.due_at.map(|d| d.to_rfc3339()),
+            review.interval_days,
+            review.ease,
+            id
+        ],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_spotlight_hidden(
+    db: &Connection,
+    id: i32,
+    until: Option<DateTime<Utc>>,
+) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET spotlight_hidden_until = ?1 WHERE id = ?2",
+        rusqlite::params![until.map(|d| d.to_rfc3339()), id],
+    )?;
+    Ok(())
+}
+
+// Moves a memo to another workspace. A hot memo lands on top of that
+// workspace's stack, unpinned.
+pub fn update_memo_workspace(db: &Connection, id: i32, workspace_id: i32) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET workspace_id = ?1, stack_position = NULL, pinned = 0 WHERE id = ?2",
+        rusqlite::params![workspace_id, id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_touched(db: &Connection, id: i32, at: DateTime<Utc>) -> Result<()> {
+    db.execute(
+        "UPDATE memos SET last_touched = ?1 WHERE id = ?2",
+        rusqlite::params![at.to_rfc3339(), id],
+    )?;
+    Ok(())
+}
+
+pub fn update_memo_content(db: &Connection, id: i32, title: &str, body: &str) -> Result<()> {
+    // Record the previous text before overwriting it
+    let (old_title, old_body): (String, String) =
+        db.query_row("SELECT title, body FROM memos WHERE id = ?1", [id], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?;
+
+    if old_title == title && old_body == body {
+        return Ok(());
+    }
+
+    db.execute(
+        "INSERT INTO memo_revisions (memo_id, title, body, revised_at) VALUES (?1, ?2, ?3, ?4)",
+        rusqlite::params![id, old_title, old_body, Utc::now().to_rfc3339()],
+    )?;
+
+    db.execute(
+        "UPDATE memos SET title = ?1, body = ?2 WHERE id = ?3",
+        rusqlite::params![title, body, id],
+    )?;
+    Ok(())
+}
+
+pub fn load_revisions(db: &Connection, memo_id: i32) -> Result<Vec<MemoRevision>> {
+    // Newest first
+    let mut stmt = db.prepare(
+        "SELECT id, memo_id, title, body, revised_at FROM memo_revisions WHERE memo_id = ?1 ORDER BY id DESC",
+    )?;
+    let revisions = stmt
+        .query_map([memo_id], |row| {
+            let revised_at_str: String = row.get(4)?;
+            Ok(MemoRevision {
+                id: row.get(0)?,
+                memo_id: row.get(1)?,
+                title: row.get(2)?,
+                body: row.get(3)?,
+                revised_at: DateTime::parse_from_rfc3339(&revised_at_str)
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .unwrap_or_else(|_| Utc::now()),
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(revisions)
+}
+
+// Ranked full-text search, best match first. Takes an FTS5 query as built by
+// `Query::fts_query`.
+pub fn search_memos(db: &Connection, fts_query: &str) -> Result<Vec<SearchHit>> {
+    // Title matches count for more than body matches
+    let mut stmt = db.prepare(
+        "SELECT rowid,
+                highlight(memos_fts, 0, ?2, ?3),
+                snippet(memos_fts, 1, ?2, ?3, '…', 16)
+         FROM memos_fts
+         WHERE memos_fts MATCH ?1
+         ORDER BY bm25(memos_fts, 5.0, 1.0)",
+    )?;
+    let hits = stmt
+        .query_map(
+            rusqlite::params![fts_query, MATCH_START.to_string(), MATCH_END.to_string()],
+            |row| {
+                Ok(SearchHit {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    snippet: row.get(2)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(hits)
+}
+
+// Revisions are kept so that undoing the delete brings the history back; see
+// `purge_orphaned_revisions`
 pub fn delete_memo(db: &Connection, id: i32) -> Result<()> {
+    db.execute("DELETE FROM memo_tags WHERE memo_id = ?1", [id])?;
     db.execute("DELETE FROM memos WHERE id = ?1", [id])?;
     Ok(())
 }
 
-pub fn load_app_state(
+// Deletes the revisions of deleted memos, except those of memos that undo
+// or redo can still bring back
+pub fn purge_orphaned_revisions(db: &Connection, keep: &HashSet<i32>) -> Result<()> {
+    let mut stmt = db.prepare(
+        "SELECT DISTINCT memo_id FROM memo_revisions
+         WHERE memo_id NOT IN (SELECT id FROM memos)",
+    )?;
+    let orphans = stmt
+        .query_map([], |row| row.get::<_, i32>(0))?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut delete = db.prepare("DELETE FROM memo_revisions WHERE memo_id = ?1")?;
+    for id in orphans.into_iter().filter(|id| !keep.contains(id)) {
+        delete.execute([id])?;
+    }
+    Ok(())
+}
+
+pub fn load_workspaces(db: &Connection) -> Result<Vec<Workspace>> {
+    let mut stmt = db.prepare("SELECT id, name, max_hot_count FROM workspaces ORDER BY id")?;
+    let workspaces = stmt
+        .query_map([], |row| {
+            Ok(Workspace {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                max_hot_count: row.get::<_, Option<i64>>(2)?.map(|count| count as usize),
+            })
+        })?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(workspaces)
+}
+
+pub fn add_workspace(db: &Connection, name: &str) -> Result<i32> {
+    db.execute("INSERT INTO workspaces (name) VALUES (?1)", [name])?;
+    Ok(db.last_insert_rowid() as i32)
+}
+
+pub fn update_workspace_max_hot_count(
     db: &Connection,
-) -> Result<(f32, bool, String, f32, f32, Option<f32>, Option<f32>)> {
-    let result = db.query_row(
-        "SELECT memo_input_height, always_on_top, new_memo_text, window_width, window_height, window_x, window_y FROM app_state WHERE id = 1",
+    id: i32,
+    max_hot_count: Option<usize>,
+) -> Result<()> {
+    db.execute(
+        "UPDATE workspaces SET max_hot_count = ?1 WHERE id = ?2",
+        rusqlite::params![max_hot_count.map(|count| count as i64), id],
+    )?;
+    Ok(())
+}
+
+pub fn load_app_state(db: &Connection) -> Result<AppState> {
+    db.query_row(
+        "SELECT memo_input_height, always_on_top, new_memo_text, window_width, window_height, window_x, window_y, workspace_id FROM app_state WHERE id = 1",
         [],
         |row| {
-            Ok((
-                row.get::<_, f64>(0)? as f32, // memo_input_height
-                row.get::<_, i32>(1)? != 0,   // always_on_top
-                row.get::<_, String>(2)?,     // new_memo_text
-                row.get::<_, f64>(3)? as f32, // window_width
-                row.get::<_, f64>(4)? as f32, // window_height
-                row.get::<_, Option<f64>>(5)?.map(|x| x as f32), // window_x
-                row.get::<_, Option<f64>>(6)?.map(|y| y as f32), // window_y
-            ))
+            Ok(AppState {
+                memo_input_height: row.get::<_, f64>(0)? as f32,
+                always_on_top: row.get::<_, i32>(1)? != 0,
+                new_memo_text: row.get(2)?,
+                window_width: row.get::<_, f64>(3)? as f32,
+                window_height: row.get::<_, f64>(4)? as f32,
+                window_x: row.get::<_, Option<f64>>(5)?.map(|x| x as f32),
+                window_y: row.get::<_, Option<f64>>(6)?.map(|y| y as f32),
+                workspace_id: row.get(7)?,
+            })
         },
-    )?;
-    Ok(result)
+    )
 }
 
 pub fn load_window_state() -> Result<(f32, f32, Option<f32>, Option<f32>)> {
@@ -227,30 +550,27 @@ pub fn load_window_state() -> Result<(f32, f32, Option<f32>, Option<f32>)> {
     let db = Connection::open(&db_path)?;
     create_tables(&db)?;
 
-    let (_, _, _, window_width, window_height, window_x, window_y) = load_app_state(&db)?;
-    Ok((window_width, window_height, window_x, window_y))
+    let state = load_app_state(&db)?;
+    Ok((
+        state.window_width,
+        state.window_height,
+        state.window_x,
+        state.window_y,
+    ))
 }
 
-pub fn save_app_state(
-    db: &Connection,
-    memo_input_height: f32,
-    always_on_top: bool,
-    new_memo_text: &str,
-    window_width: f32,
-    window_height: f32,
-    window_x: Option<f32>,
-    window_y: Option<f32>,
-) -> Result<()> {
+pub fn save_app_state(db: &Connection, state: &AppState) -> Result<()> {
     db.execute(
-        "UPDATE app_state SET memo_input_height = ?1, always_on_top = ?2, new_memo_text = ?3, window_width = ?4, window_height = ?5, window_x = ?6, window_y = ?7 WHERE id = 1",
+        "UPDATE app_state SET memo_input_height = ?1, always_on_top = ?2, new_memo_text = ?3, window_width = ?4, window_height = ?5, window_x = ?6, window_y = ?7, workspace_id = ?8 WHERE id = 1",
         rusqlite::params![
-            memo_input_height as f64,
-            if always_on_top { 1 } else { 0 },
-            new_memo_text,
-            window_width as f64,
-            window_height as f64,
-            window_x.map(|x| x as f64),
-            window_y.map(|y| y as f64)
+            state.memo_input_height as f64,
+            if state.always_on_top { 1 } else { 0 },
+            state.new_memo_text,
+            state.window_width as f64,
+            state.window_height as f64,
+            state.window_x.map(|x| x as f64),
+            state.window_y.map(|y| y as f64),
+            state.workspace_id
         ],
     )?;
     Ok(())
@@ -0,0 +1,95 @@
+// Minimal line-based diff used by the revision history view
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+// Longest-common-subsequence diff of two texts, line by line
+pub fn line_diff(old: &str, new: &str) -> Vec<DiffLine> {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let (n, m) = (old_lines.len(), new_lines.len());
+
+    // lcs[i][j] = length of LCS of old_lines[i..] and new_lines[j..]
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_lines[i] == new_lines[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old_lines[i] == new_lines[j] {
+            result.push(DiffLine::Same(old_lines[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old_lines[i].to_string()));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new_lines[j].to_string()));
+            j += 1;
+        }
+    }
+    result.extend(
+        old_lines[i..]
+            .iter()
+            .map(|l| DiffLine::Removed(l.to_string())),
+    );
+    result.extend(
+        new_lines[j..]
+            .iter()
+            .map(|l| DiffLine::Added(l.to_string())),
+    );
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use DiffLine::*;
+
+    fn lines(diff: &[(char, &str)]) -> Vec<DiffLine> {
+        diff.iter()
+            .map(|&(kind, text)| match kind {
+                '+' => Added(text.to_string()),
+                '-' => Removed(text.to_string()),
+                _ => Same(text.to_string()),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        assert_eq!(
+            line_diff("a\nb\nc", "a\nx\nc"),
+            lines(&[(' ', "a"), ('-', "b"), ('+', "x"), (' ', "c")])
+        );
+        assert_eq!(
+            line_diff("a\nc", "a\nb\nc\nd"),
+            lines(&[(' ', "a"), ('+', "b"), (' ', "c"), ('+', "d")])
+        );
+        assert_eq!(
+            line_diff("a\nb\nc", "c"),
+            lines(&[('-', "a"), ('-', "b"), (' ', "c")])
+        );
+    }
+
+    #[test]
+    fn diffs_empty_texts() {
+        assert_eq!(line_diff("", ""), Vec::new());
+        assert_eq!(line_diff("", "a"), lines(&[('+', "a")]));
+        assert_eq!(line_diff("a", ""), lines(&[('-', "a")]));
+        assert_eq!(line_diff("a\nb", "a\nb"), lines(&[(' ', "a"), (' ', "b")]));
+    }
+}
@@ -13,6 +13,19 @@ pub const SEARCH: &str = "\u{E30C}"; // Magnifying glass for search
 pub const DELAY: &str = "\u{E492}"; // Clock icon for delayed memos
 pub const EXPAND: &str = "\u{E13A}"; // Caret up for expand
 pub const COLLAPSE: &str = "\u{E136}"; // Caret down for collapse
+pub const HISTORY: &str = "\u{E1A0}"; // Clock counter-clockwise for revision history
+pub const UNDO: &str = "\u{E038}"; // Arrow counter-clockwise for undo
+pub const REDO: &str = "\u{E036}"; // Arrow clockwise for redo
+pub const PIN: &str = "\u{E65C}"; // Push pin simple for pinned hot memos
+pub const WARNING: &str = "\u{E4E0}"; // Warning for hot memos about to decay to cold
+pub const SNOOZE: &str = "\u{E5EE}"; // Bell with z's for snoozing a memo
+pub const REPEAT: &str = "\u{E3F6}"; // Repeat arrows for recurring memos
+pub const REVIEW: &str = "\u{E0F8}"; // Cards for the cold memo review session
+pub const SKIP_BACK: &str = "\u{E5A4}"; // Skip back to the previous spotlight memo
+pub const SKIP: &str = "\u{E5A6}"; // Skip forward to the next spotlight memo
+pub const NOT_INTERESTED: &str = "\u{E224}"; // Eye slash for hiding a memo from the spotlight
+pub const WORKSPACE: &str = "\u{E0EE}"; // Briefcase for workspaces
+pub const TAG: &str = "\u{E478}"; // Tag for memo tags and the tag filter
 pub const ALWAYS_ON_TOP: &str = "\u{E3E2}"; // X icon for closing/canceling
 
 // Helper function to render an icon with default size
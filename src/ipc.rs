@@ -0,0 +1,215 @@
+// Local control channel between command-line invocations and the running
+// window. The window listens on a Unix socket in the data directory; each
+// connection carries one JSON-encoded `Invocation` line and gets one
+// `Response` line back. Commands run against the window's own state, so
+// `memo-stack add ...` shows up in the hot stack immediately and queries like
+// `memo-stack list` see exactly what the window shows.
+//
+// If the window's UI thread does not pick a command up quickly (it is busy or
+// hung), the command is withdrawn and the caller runs it against the database
+// itself. A command is claimed by exactly one side, so it never runs twice.
+
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::mpsc;
+
+use crate::app::MemoApp;
+use crate::cli::Invocation;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    pub output: String,
+    #[serde(default)]
+    pub busy: bool, // The window did not take the command; run it directly
+}
+
+// Who gets to run a forwarded command
+const WAITING: u8 = 0;
+const CLAIMED: u8 = 1; // By the window's UI thread
+const WITHDRAWN: u8 = 2; // Handed back to the caller
+
+// A forwarded command waiting to be executed on the UI thread
+pub struct PendingRequest {
+    pub invocation: Invocation,
+    pub reply: mpsc::Sender<Result<String, String>>,
+    state: Arc<AtomicU8>,
+}
+
+impl PendingRequest {
+    // Claims the command for the UI thread. Fails if it was already handed
+    // back to the caller.
+    pub fn claim(&self) -> bool {
+        self.state
+            .compare_exchange(WAITING, CLAIMED, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+}
+
+// Hands a command back to the caller. Fails if the UI thread already claimed it.
+fn withdraw(state: &AtomicU8) -> bool {
+    state
+        .compare_exchange(WAITING, WITHDRAWN, Ordering::AcqRel, Ordering::Acquire)
+        .is_ok()
+}
+
+pub fn socket_path() -> PathBuf {
+    MemoApp::data_dir().join("memo-stack.sock")
+}
+
+#[cfg(unix)]
+mod unix {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    use eframe::egui;
+
+    use super::{PendingRequest, Response, socket_path, withdraw};
+    use crate::cli::Invocation;
+
+    // How long a command waits for the UI thread to pick it up
+    const PICKUP_TIMEOUT: Duration = Duration::from_secs(1);
+    // Time allowed for writing a request, and for running it once picked up
+    const TIMEOUT: Duration = Duration::from_secs(3);
+
+    // Starts listening in a background thread. Returns None if another window
+    // already owns the socket or it cannot be created.
+    pub fn start_server(ctx: egui::Context) -> Option<mpsc::Receiver<PendingRequest>> {
+        let path = socket_path();
+        if path.exists() {
+            if UnixStream::connect(&path).is_ok() {
+                eprintln!("Another memo-stack window is already listening for commands");
+                return None;
+            }
+            // Stale socket left behind by a crashed window
+            let _ = std::fs::remove_file(&path);
+        }
+
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Error creating command socket: {}", e);
+                return None;
+            }
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            // One thread per connection so a slow client can't hold up others
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                let ctx = ctx.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = handle_connection(stream, &sender, &ctx) {
+                        eprintln!("Error handling command: {}", e);
+                    }
+                });
+            }
+        });
+
+        Some(receiver)
+    }
+
+    fn handle_connection(
+        stream: UnixStream,
+        sender: &mpsc::Sender<PendingRequest>,
+        ctx: &egui::Context,
+    ) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(TIMEOUT))?;
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+
+        let result = match serde_json::from_str::<Invocation>(&line) {
+            Ok(invocation) => {
+                let (reply, response) = mpsc::channel();
+                let request = PendingRequest {
+                    invocation,
+                    reply,
+                    state: Default::default(),
+                };
+                let state = request.state.clone();
+                let _ = sender.send(request);
+                // Wake the UI thread so the command runs right away
+                ctx.request_repaint();
+
+                match response.recv_timeout(PICKUP_TIMEOUT) {
+                    Ok(result) => Some(result),
+                    Err(_) if withdraw(&state) => None,
+                    // Claimed just now; it is running
+                    Err(_) => Some(
+                        response
+                            .recv_timeout(TIMEOUT)
+                            .unwrap_or_else(|_| Err("the window did not respond".to_string())),
+                    ),
+                }
+            }
+            Err(e) => Some(Err(format!("invalid request: {}", e))),
+        };
+
+        let response = match result {
+            Some(Ok(output)) => Response {
+                ok: true,
+                output,
+                busy: false,
+            },
+            Some(Err(output)) => Response {
+                ok: false,
+                output,
+                busy: false,
+            },
+            None => Response {
+                ok: false,
+                output: String::new(),
+                busy: true,
+            },
+        };
+        let mut writer = &stream;
+        writeln!(writer, "{}", serde_json::to_string(&response)?)?;
+        Ok(())
+    }
+
+    // Forwards a command to the running window. Returns None if no window is
+    // listening or it is too busy to take the command, in which case the
+    // caller works on the database directly.
+    pub fn send(invocation: &Invocation) -> Option<Result<String, String>> {
+        let stream = UnixStream::connect(socket_path()).ok()?;
+        stream.set_write_timeout(Some(TIMEOUT)).ok()?;
+        // The window always answers within PICKUP_TIMEOUT + TIMEOUT, so a
+        // longer silence means it is hung
+        stream
+            .set_read_timeout(Some(PICKUP_TIMEOUT + TIMEOUT + Duration::from_secs(1)))
+            .ok()?;
+
+        let request = serde_json::to_string(invocation).ok()?;
+        let mut writer = &stream;
+        writeln!(writer, "{}", request).ok()?;
+
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line).ok()?;
+        match serde_json::from_str::<Response>(&line) {
+            Ok(Response { busy: true, .. }) => None,
+            Ok(Response {
+                ok: true, output, ..
+            }) => Some(Ok(output)),
+            Ok(Response { output, .. }) => Some(Err(output)),
+            Err(e) => Some(Err(format!("invalid reply from the running window: {}", e))),
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use unix::{send, start_server};
+
+#[cfg(not(unix))]
+pub fn start_server(_ctx: eframe::egui::Context) -> Option<mpsc::Receiver<PendingRequest>> {
+    None
+}
+
+#[cfg(not(unix))]
+pub fn send(_invocation: &Invocation) -> Option<Result<String, String>> {
+    None
+}
@@ -0,0 +1,203 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use crate::models::MemoData;
+
+// Maximum number of operations kept for undo
+const MAX_ENTRIES: usize = 100;
+
+// State of the memos touched by one operation, plus the full hot stack order
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub hot_stack: Vec<i32>,
+    pub memos: Vec<(i32, Option<MemoData>)>, // None = memo did not exist
+}
+
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub label: String,
+    pub before: Snapshot,
+    pub after: Snapshot,
+}
+
+impl JournalEntry {
+    // Builds an entry from the state before and after an operation.
+    // Returns None if nothing changed.
+    pub fn from_states(
+        label: &str,
+        stack_before: &[i32],
+        memos_before: &HashMap<i32, MemoData>,
+        stack_after: &[i32],
+        memos_after: &HashMap<i32, MemoData>,
+    ) -> Option<Self> {
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+
+        for (id, old) in memos_before {
+            let new = memos_after.get(id);
+            if new != Some(old) {
+                before.push((*id, Some(old.clone())));
+                after.push((*id, new.cloned()));
+            }
+        }
+        for (id, new) in memos_after {
+            if !memos_before.contains_key(id) {
+                before.push((*id, None));
+                after.push((*id, Some(new.clone())));
+            }
+        }
+
+        if before.is_empty() && stack_before == stack_after {
+            return None;
+        }
+
+        Some(Self {
+            label: label.to_string(),
+            before: Snapshot {
+                hot_stack: stack_before.to_vec(),
+                memos: before,
+            },
+            after: Snapshot {
+                hot_stack: stack_after.to_vec(),
+                memos: after,
+            },
+        })
+    }
+}
+
+// The live memo with only the fields that one operation changed (between
+// `from` and `to`) set to their values in `to`. Anything changed since, such
+// as a later edit or spotlight and review stats, is kept.
+pub fn restore_changed_fields(live: &MemoData, to: &MemoData, from: &MemoData) -> MemoData {
+    let mut memo = live.clone();
+    macro_rules! restore {
+        ($($field:ident),*) => {
+            $(
+                if to.$field != from.$field {
+                    memo.$field = to.$field.clone();
+                }
+            )*
+        };
+    }
+    restore!(
+        workspace_id,
+        title,
+        body,
+        status,
+        creation_date,
+        moved_to_done_date,
+        wake_at,
+        wake_target,
+        tags,
+        pinned,
+        last_touched,
+        recurrence,
+        last_spotlighted_at,
+        spotlight_count,
+        spotlight_hidden_until,
+        review
+    );
+    memo
+}
+
+// Memos that differ between two loads of the same memos, ignoring UI-only state
+pub fn changed_memo_ids(
+    old: &HashMap<i32, MemoData>,
+    new: &HashMap<i32, MemoData>,
+) -> HashSet<i32> {
+    let mut changed: HashSet<i32> = old
+        .iter()
+        .filter(|(id, memo)| {
+            new.get(id).is_none_or(|other| {
+                *other
+                    != MemoData {
+                        expanded: other.expanded,
+                        ..(*memo).clone()
+                    }
+            })
+        })
+        .map(|(id, _)| *id)
+        .collect();
+    changed.extend(new.keys().filter(|id| !old.contains_key(id)));
+    changed
+}
+
+#[derive(Debug, Default)]
+pub struct Journal {
+    undo: Vec<JournalEntry>,
+    redo: Vec<JournalEntry>,
+}
+
+impl Journal {
+    // Returns whether older entries were dropped to make room
+    pub fn record(&mut self, entry: JournalEntry) -> bool {
+        self.undo.push(entry);
+        let mut dropped = !self.redo.is_empty();
+        if self.undo.len() > MAX_ENTRIES {
+            self.undo.remove(0);
+            dropped = true;
+        }
+        self.redo.clear();
+        dropped
+    }
+
+    pub fn take_undo(&mut self) -> Option<JournalEntry> {
+        self.undo.pop()
+    }
+
+    pub fn take_redo(&mut self) -> Option<JournalEntry> {
+        self.redo.pop()
+    }
+
+    pub fn push_redo(&mut self, entry: JournalEntry) {
+        self.redo.push(entry);
+    }
+
+    pub fn push_undo(&mut self, entry: JournalEntry) {
+        self.undo.push(entry);
+    }
+
+    // Drops the entries touching any of the given memos, e.g. because they
+    // were changed outside the journal. Returns whether any were dropped.
+    pub fn forget(&mut self, ids: &HashSet<i32>) -> bool {
+        let touches = |entry: &JournalEntry| {
+            entry
+                .before
+                .memos
+                .iter()
+                .chain(&entry.after.memos)
+                .any(|(id, _)| ids.contains(id))
+        };
+        let len = self.undo.len() + self.redo.len();
+        self.undo.retain(|entry| !touches(entry));
+        self.redo.retain(|entry| !touches(entry));
+        self.undo.len() + self.redo.len() != len
+    }
+
+    // Memos that some undo or redo entry can bring back
+    pub fn memo_ids(&self) -> HashSet<i32> {
+        self.undo
+            .iter()
+            .chain(&self.redo)
+            .flat_map(|entry| entry.before.memos.iter().chain(&entry.after.memos))
+            .map(|(id, _)| *id)
+            .collect()
+    }
+
+    pub fn undo_label(&self) -> Option<&str> {
+        self.undo.last().map(|entry| entry.label.as_str())
+    }
+
+    pub fn redo_label(&self) -> Option<&str> {
+        self.redo.last().map(|entry| entry.label.as_str())
+    }
+}
+
+// Transient notice with an "Undo" button, shown after destructive actions.
+// Automatic changes that are not journaled show it without the button.
+#[derive(Debug, Clone)]
+pub struct UndoToast {
+    pub message: String,
+    pub shown_at: Instant,
+    pub undoable: bool,
+}
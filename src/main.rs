@@ -1,7 +1,18 @@
 mod app;
+mod cli;
 mod database;
+mod diff;
 mod icons;
+mod ipc;
+mod journal;
+mod migrations;
 mod models;
+mod query;
+mod recurrence;
+mod review;
+mod schedule;
+mod staged;
+mod tags;
 mod ui;
 
 use app::MemoApp;
@@ -14,9 +25,20 @@ static ATKINSON_FONT: &[u8] = include_bytes!(
 static PHOSPHOR_ICONS: &[u8] = include_bytes!("../fonts/phosphor_icons/regular/Phosphor.ttf");
 
 fn main() -> Result<(), eframe::Error> {
+    // Any arguments run a headless command instead of opening the window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     // Load saved window state from database
-    let (window_width, window_height, window_x, window_y) =
-        database::load_window_state().unwrap_or((800.0, 600.0, None, None));
+    let (window_width, window_height, window_x, window_y) = match database::load_window_state() {
+        Ok(state) => state,
+        Err(e) => {
+            eprintln!("Error opening database: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Build viewport with saved size and position
     let mut viewport_builder = egui::ViewportBuilder::default()
@@ -45,7 +67,8 @@ fn main() -> Result<(), eframe::Error> {
             let visuals = ui::theme::configure_visuals();
             cc.egui_ctx.set_visuals(visuals);
 
-            let app = MemoApp::new().expect("Failed to initialize app");
+            let mut app = MemoApp::new().expect("Failed to initialize app");
+            app.start_ipc(cc.egui_ctx.clone());
             Ok(Box::new(app))
         }),
     )
@@ -0,0 +1,519 @@
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::types::Type;
+use rusqlite::{Connection, OptionalExtension, Result, ffi};
+
+use crate::tags;
+
+// Schema migrations, applied in order. The database's `user_version` is the
+// number of migrations that have already been applied, so new migrations must
+// only ever be appended to this list.
+const MIGRATIONS: &[fn(&Connection) -> Result<()>] = &[
+    initial_schema,
+    memo_revisions,
+    stack_positions,
+    full_text_search,
+    memo_tags,
+    pinned_memos,
+    last_touched,
+    wake_at,
+    recurrence,
+    wake_targets,
+    spotlight_stats,
+    review_schedule,
+    spotlight_hidden,
+    workspaces,
+];
+
+pub fn run(db: &Connection) -> Result<()> {
+    let version: usize = db.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    if version > MIGRATIONS.len() {
+        return Err(rusqlite::Error::SqliteFailure(
+            ffi::Error::new(ffi::SQLITE_ERROR),
+            Some(format!(
+                "database schema version {} is newer than this build of memo-stack supports ({}); please upgrade memo-stack",
+                version,
+                MIGRATIONS.len()
+            )),
+        ));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = db.unchecked_transaction()?;
+        migration(&tx)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+    }
+
+    Ok(())
+}
+
+fn has_column(db: &Connection, table: &str, column: &str) -> Result<bool> {
+    let mut stmt = db.prepare(&format!("PRAGMA table_info({})", table))?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>>>()?;
+    Ok(names.iter().any(|name| name == column))
+}
+
+fn add_column_if_missing(
+    db: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<()> {
+    if !has_column(db, table, column)? {
+        db.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// 1: Base schema. Databases created before versioning have user_version 0 and
+// may be missing columns that were added later, so fill those in explicitly.
+fn initial_schema(db: &Connection) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS memos (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            status TEXT NOT NULL DEFAULT 'hot',
+            creation_date TEXT NOT NULL,
+            moved_to_done_date TEXT,
+            delay_minutes INTEGER
+        )",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS hot_stack_state (
+            id INTEGER PRIMARY KEY DEFAULT 1,
+            stack_json TEXT NOT NULL DEFAULT '[]'
+        )",
+        [],
+    )?;
+
+    db.execute(
+        "INSERT OR IGNORE INTO hot_stack_state (id, stack_json) VALUES (1, '[]')",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS app_state (
+            id INTEGER PRIMARY KEY DEFAULT 1,
+            memo_input_height REAL NOT NULL DEFAULT 80.0,
+            always_on_top INTEGER NOT NULL DEFAULT 0,
+            new_memo_text TEXT NOT NULL DEFAULT '',
+            window_width REAL NOT NULL DEFAULT 800.0,
+            window_height REAL NOT NULL DEFAULT 600.0,
+            window_x REAL,
+            window_y REAL
+        )",
+        [],
+    )?;
+
+    db.execute("INSERT OR IGNORE INTO app_state (id) VALUES (1)", [])?;
+
+    add_column_if_missing(db, "memos", "delay_minutes", "INTEGER")?;
+    add_column_if_missing(
+        db,
+        "app_state",
+        "window_width",
+        "REAL NOT NULL DEFAULT 800.0",
+    )?;
+    add_column_if_missing(
+        db,
+        "app_state",
+        "window_height",
+        "REAL NOT NULL DEFAULT 600.0",
+    )?;
+    add_column_if_missing(db, "app_state", "window_x", "REAL")?;
+    add_column_if_missing(db, "app_state", "window_y", "REAL")?;
+
+    Ok(())
+}
+
+// 2: Previous memo text, recorded on every title/body change
+fn memo_revisions(db: &Connection) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS memo_revisions (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            memo_id INTEGER NOT NULL,
+            title TEXT NOT NULL,
+            body TEXT NOT NULL,
+            revised_at TEXT NOT NULL
+        )",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE INDEX IF NOT EXISTS idx_memo_revisions_memo_id ON memo_revisions (memo_id)",
+        [],
+    )?;
+
+    Ok(())
+}
+
+// 3: Store hot stack order per memo instead of a JSON array in hot_stack_state
+fn stack_positions(db: &Connection) -> Result<()> {
+    db.execute("ALTER TABLE memos ADD COLUMN stack_position INTEGER", [])?;
+
+    let stack_json: Option<String> = db
+        .query_row(
+            "SELECT stack_json FROM hot_stack_state WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    // A stack that can't be read fails the migration rather than losing the order
+    let hot_stack: Vec<i32> = match stack_json {
+        Some(stack_json) => serde_json::from_str(&stack_json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, Type::Text, Box::new(e)))?,
+        None => Vec::new(),
+    };
+
+    let mut stmt =
+        db.prepare("UPDATE memos SET stack_position = ?1 WHERE id = ?2 AND status = 'hot'")?;
+    for (position, id) in hot_stack.iter().enumerate() {
+        stmt.execute(rusqlite::params![position as i64, id])?;
+    }
+
+    db.execute("DROP TABLE IF EXISTS hot_stack_state", [])?;
+    Ok(())
+}
+
+// 4: FTS5 index over memo text, kept in sync with `memos` by triggers so that
+// edits made outside the app (e.g. with sqlite3) are indexed too
+fn full_text_search(db: &Connection) -> Result<()> {
+    db.execute(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS memos_fts USING fts5(
+            title,
+            body,
+            content = 'memos',
+            content_rowid = 'id',
+            tokenize = 'unicode61 remove_diacritics 2'
+        )",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE TRIGGER IF NOT EXISTS memos_fts_insert AFTER INSERT ON memos BEGIN
+            INSERT INTO memos_fts (rowid, title, body) VALUES (new.id, new.title, new.body);
+        END",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE TRIGGER IF NOT EXISTS memos_fts_delete AFTER DELETE ON memos BEGIN
+            INSERT INTO memos_fts (memos_fts, rowid, title, body)
+            VALUES ('delete', old.id, old.title, old.body);
+        END",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE TRIGGER IF NOT EXISTS memos_fts_update AFTER UPDATE OF title, body ON memos BEGIN
+            INSERT INTO memos_fts (memos_fts, rowid, title, body)
+            VALUES ('delete', old.id, old.title, old.body);
+            INSERT INTO memos_fts (rowid, title, body) VALUES (new.id, new.title, new.body);
+        END",
+        [],
+    )?;
+
+    // Index memos that already exist
+    db.execute("INSERT INTO memos_fts (memos_fts) VALUES ('rebuild')", [])?;
+    Ok(())
+}
+
+// 5: Tags parsed from #hashtags in memo text
+fn memo_tags(db: &Connection) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS memo_tags (
+            memo_id INTEGER NOT NULL,
+            tag TEXT NOT NULL,
+            PRIMARY KEY (memo_id, tag)
+        )",
+        [],
+    )?;
+
+    db.execute(
+        "CREATE INDEX IF NOT EXISTS idx_memo_tags_tag ON memo_tags (tag)",
+        [],
+    )?;
+
+    // Tag existing memos
+    let mut stmt = db.prepare("SELECT id, title, body FROM memos")?;
+    let memos = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut insert =
+        db.prepare("INSERT OR IGNORE INTO memo_tags (memo_id, tag) VALUES (?1, ?2)")?;
+    for (id, title, body) in memos {
+        for tag in tags::parse_tags(&title, &body) {
+            insert.execute(rusqlite::params![id, tag])?;
+        }
+    }
+
+    Ok(())
+}
+
+// 6: Pinned hot memos
+fn pinned_memos(db: &Connection) -> Result<()> {
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+// 7: When each memo was last reordered, expanded, edited or moved to hot
+fn last_touched(db: &Connection) -> Result<()> {
+    db.execute("ALTER TABLE memos ADD COLUMN last_touched TEXT", [])?;
+    db.execute("UPDATE memos SET last_touched = creation_date", [])?;
+    Ok(())
+}
+
+// 8: Absolute wake-up time for delayed memos instead of minutes since creation
+fn wake_at(db: &Connection) -> Result<()> {
+    db.execute("ALTER TABLE memos ADD COLUMN wake_at TEXT", [])?;
+
+    let mut stmt = db.prepare(
+        "SELECT id, creation_date, delay_minutes FROM memos
+         WHERE delay_minutes IS NOT NULL AND status = 'delayed'",
+    )?;
+    let delays = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i32>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut update = db.prepare("UPDATE memos SET wake_at = ?1 WHERE id = ?2")?;
+    for (id, creation_date, delay_minutes) in delays {
+        let Ok(creation_date) = DateTime::parse_from_rfc3339(&creation_date) else {
+            continue;
+        };
+        let wake_at = creation_date.with_timezone(&Utc) + Duration::minutes(delay_minutes);
+        update.execute(rusqlite::params![wake_at.to_rfc3339(), id])?;
+    }
+
+    db.execute("ALTER TABLE memos DROP COLUMN delay_minutes", [])?;
+    Ok(())
+}
+
+// 9: Recurrence rules for routine memos (see recurrence.rs)
+fn recurrence(db: &Connection) -> Result<()> {
+    db.execute("ALTER TABLE memos ADD COLUMN recurrence TEXT", [])?;
+    Ok(())
+}
+
+// 10: Where each delayed memo goes when it wakes up
+fn wake_targets(db: &Connection) -> Result<()> {
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN wake_target TEXT NOT NULL DEFAULT 'hot'",
+        [],
+    )?;
+    Ok(())
+}
+
+// 11: When and how often each memo was shown in the cold spotlight
+fn spotlight_stats(db: &Connection) -> Result<()> {
+    db.execute("ALTER TABLE memos ADD COLUMN last_spotlighted_at TEXT", [])?;
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN spotlight_count INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    Ok(())
+}
+
+// 12: Spaced-repetition review schedule for cold memos (see review.rs)
+fn review_schedule(db: &Connection) -> Result<()> {
+    db.execute("ALTER TABLE memos ADD COLUMN review_due_at TEXT", [])?;
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN review_interval_days INTEGER NOT NULL DEFAULT 0",
+        [],
+    )?;
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN review_ease REAL NOT NULL DEFAULT 2.5",
+        [],
+    )?;
+    Ok(())
+}
+
+// 13: Memos hidden from the cold spotlight for a while
+fn spotlight_hidden(db: &Connection) -> Result<()> {
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN spotlight_hidden_until TEXT",
+        [],
+    )?;
+    Ok(())
+}
+
+// 14: Workspaces, each with its own hot stack and cold pile. Existing memos
+// go into a default workspace.
+fn workspaces(db: &Connection) -> Result<()> {
+    db.execute(
+        "CREATE TABLE IF NOT EXISTS workspaces (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            name TEXT NOT NULL UNIQUE,
+            max_hot_count INTEGER
+        )",
+        [],
+    )?;
+    db.execute(
+        "INSERT OR IGNORE INTO workspaces (id, name) VALUES (1, 'Default')",
+        [],
+    )?;
+
+    db.execute(
+        "ALTER TABLE memos ADD COLUMN workspace_id INTEGER NOT NULL DEFAULT 1",
+        [],
+    )?;
+    db.execute(
+        "CREATE INDEX IF NOT EXISTS idx_memos_workspace_id ON memos (workspace_id)",
+        [],
+    )?;
+    db.execute(
+        "ALTER TABLE app_state ADD COLUMN workspace_id INTEGER NOT NULL DEFAULT 1",
+        [],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A database as created by the version before schema migrations
+    fn baseline_database(stack_json: &str) -> Connection {
+        let db = Connection::open_in_memory().unwrap();
+        db.execute_batch(
+            "CREATE TABLE memos (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                body TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'hot',
+                creation_date TEXT NOT NULL,
+                moved_to_done_date TEXT,
+                delay_minutes INTEGER
+            );
+            CREATE TABLE hot_stack_state (
+                id INTEGER PRIMARY KEY DEFAULT 1,
+                stack_json TEXT NOT NULL DEFAULT '[]'
+            );
+            CREATE TABLE app_state (
+                id INTEGER PRIMARY KEY DEFAULT 1,
+                memo_input_height REAL NOT NULL DEFAULT 80.0,
+                always_on_top INTEGER NOT NULL DEFAULT 0,
+                new_memo_text TEXT NOT NULL DEFAULT ''
+            );
+            INSERT INTO app_state (id, new_memo_text) VALUES (1, 'draft');
+            INSERT INTO memos (id, title, body, status, creation_date, delay_minutes) VALUES
+                (1, 'First', 'about #work', 'hot', '2026-10-01T10:00:00+00:00', NULL),
+                (2, 'Second', '', 'hot', '2026-10-02T10:00:00+00:00', NULL),
+                (3, 'Later', '', 'delayed', '2026-10-03T10:00:00+00:00', 90),
+                (4, 'Woken', '', 'hot', '2026-10-04T10:00:00+00:00', 30);",
+        )
+        .unwrap();
+        db.execute(
+            "INSERT INTO hot_stack_state (id, stack_json) VALUES (1, ?1)",
+            [stack_json],
+        )
+        .unwrap();
+        db
+    }
+
+    fn user_version(db: &Connection) -> usize {
+        db.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn migrates_the_baseline_schema() {
+        let db = baseline_database("[4, 2, 1]");
+        run(&db).unwrap();
+        assert_eq!(user_version(&db), MIGRATIONS.len());
+
+        let draft: String = db
+            .query_row("SELECT new_memo_text FROM app_state", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(draft, "draft");
+
+        let mut stmt = db
+            .prepare("SELECT id, title, stack_position, wake_at FROM memos ORDER BY id")
+            .unwrap();
+        let memos = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, i32>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, Option<String>>(3)?,
+                ))
+            })
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+        assert_eq!(
+            memos,
+            vec![
+                (1, "First".to_string(), Some(2), None),
+                (2, "Second".to_string(), Some(1), None),
+                (
+                    3,
+                    "Later".to_string(),
+                    None,
+                    Some("2026-10-03T11:30:00+00:00".to_string())
+                ),
+                (4, "Woken".to_string(), Some(0), None),
+            ]
+        );
+
+        let tags: Vec<String> = db
+            .prepare("SELECT tag FROM memo_tags WHERE memo_id = 1")
+            .unwrap()
+            .query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(tags, vec!["work"]);
+
+        // Running again changes nothing
+        run(&db).unwrap();
+        assert_eq!(user_version(&db), MIGRATIONS.len());
+    }
+
+    #[test]
+    fn keeps_the_old_schema_when_a_migration_fails() {
+        let db = baseline_database("not json");
+        assert!(run(&db).is_err());
+        // Migrations before the failing one are kept
+        assert_eq!(user_version(&db), 2);
+        let stack_json: String = db
+            .query_row("SELECT stack_json FROM hot_stack_state", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(stack_json, "not json");
+    }
+
+    #[test]
+    fn rejects_newer_databases() {
+        let db = Connection::open_in_memory().unwrap();
+        db.pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+            .unwrap();
+        assert!(run(&db).is_err());
+    }
+}
@@ -1,17 +1,26 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::recurrence::Recurrence;
+use crate::review::ReviewSchedule;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Config {
     pub max_hot_count: usize,
     pub cold_spotlight_interval_seconds: u64,
+    pub cold_spotlight_slots: usize, // Cold memos shown at once, each on its own timer
     pub tab_spaces: usize,
     pub memo_input_height_min: f32,
     pub memo_input_height_max: f32,
-    pub cold_spotlight_bottom_spacing: f32,
+    pub cold_spotlight_bottom_spacing: f32, // Height kept for each spotlight slot
     pub pause_spotlight_when_expanded: bool,
     pub memo_input_space_buffer: f32,
+    pub max_pinned_count: usize, // Pinned hot memos are never evicted to cold
+    pub eviction_policy: EvictionPolicy,
+    pub hot_decay_hours: u64, // Idle hot memos move to cold after this long (0 disables)
+    pub hot_decay_warning_hours: u64, // Warn this long before a memo decays (0 disables)
+    pub spotlight_strategy: SpotlightStrategy,
 }
 
 impl Default for Config {
@@ -19,17 +28,44 @@ impl Default for Config {
         Self {
             max_hot_count: 7,
             cold_spotlight_interval_seconds: 60,
+            cold_spotlight_slots: 1,
             tab_spaces: 2,
             memo_input_height_min: 38.0,
             memo_input_height_max: 1200.0,
             cold_spotlight_bottom_spacing: 84.0,
             pause_spotlight_when_expanded: true,
             memo_input_space_buffer: 58.0,
+            max_pinned_count: 3,
+            eviction_policy: EvictionPolicy::BottomOfStack,
+            hot_decay_hours: 0,
+            hot_decay_warning_hours: 12,
+            spotlight_strategy: SpotlightStrategy::Uniform,
         }
     }
 }
 
+// Which hot memo goes cold when the stack grows past `max_hot_count`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    BottomOfStack,
+    LeastRecentlyTouched,
+    OldestCreated,
+    AskUser, // Leave the stack over the limit and let the user pick
+}
+
+// How the cold spotlight picks its next memo
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpotlightStrategy {
+    Uniform,                  // Any cold memo, at random
+    LeastRecentlySpotlighted, // At random, weighted by time since last shown
+    OldestFirst,              // Oldest memo among the least shown
+    SpacedRepetition,         // Most overdue, shown at doubling intervals
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum MemoStatus {
     Hot,
     Cold,
@@ -47,18 +83,106 @@ impl MemoStatus {
             _ => MemoStatus::Hot,
         }
     }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MemoStatus::Hot => "hot",
+            MemoStatus::Cold => "cold",
+            MemoStatus::Done => "done",
+            MemoStatus::Delayed => "delayed",
+        }
+    }
 }
 
-#[derive(Debug, Clone)]
+// Where a delayed memo goes when it wakes up
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WakeTarget {
+    Hot,
+    Cold,
+    Spotlight, // Cold, and shown in the cold spotlight next
+}
+
+impl WakeTarget {
+    pub fn from_string(s: &str) -> Self {
+        match s {
+            "cold" => WakeTarget::Cold,
+            "spotlight" => WakeTarget::Spotlight,
+            _ => WakeTarget::Hot,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WakeTarget::Hot => "hot",
+            WakeTarget::Cold => "cold",
+            WakeTarget::Spotlight => "spotlight",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MemoData {
     pub id: i32,
+    pub workspace_id: i32,
     pub title: String,
     pub body: String,
     pub status: MemoStatus,
     pub creation_date: DateTime<Utc>,
     pub moved_to_done_date: Option<DateTime<Utc>>,
-    pub delay_minutes: Option<u32>, // Minutes to delay from creation_date
-    pub expanded: bool,             // UI state only
+    pub wake_at: Option<DateTime<Utc>>, // When a delayed memo wakes up
+    pub wake_target: WakeTarget,        // Where it goes when it does
+    pub tags: Vec<String>,              // From #hashtags in the text, sorted
+    pub pinned: bool,                   // Hot memos only; skipped when the stack overflows
+    pub last_touched: DateTime<Utc>,    // Last reordered, expanded, edited or moved to hot
+    pub recurrence: Option<Recurrence>, // Spawns the next occurrence when moved to done
+    pub last_spotlighted_at: Option<DateTime<Utc>>, // Last shown in the cold spotlight
+    pub spotlight_count: u32,           // Times shown in the cold spotlight
+    pub spotlight_hidden_until: Option<DateTime<Utc>>, // "Not interested" in the spotlight until
+    #[serde(skip)]
+    pub review: ReviewSchedule, // When the review session next asks about this cold memo
+    #[serde(skip)]
+    pub expanded: bool, // UI state only
+}
+
+// A separate context ("work", "home", ...) with its own hot stack and cold pile
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub id: i32,
+    pub name: String,
+    pub max_hot_count: Option<usize>, // None = `Config::max_hot_count`
+}
+
+// Previous title/body of a memo, recorded whenever its content changes
+#[derive(Debug, Clone)]
+pub struct MemoRevision {
+    pub id: i64,
+    pub memo_id: i32,
+    pub title: String,
+    pub body: String,
+    pub revised_at: DateTime<Utc>,
+}
+
+// A full-text search match. Title and snippet contain the matched terms
+// wrapped in `database::MATCH_START` / `database::MATCH_END`.
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub id: i32,
+    pub title: String,
+    pub snippet: String,
+}
+
+// Persisted UI/window state (single row in app_state)
+#[derive(Debug, Clone)]
+pub struct AppState {
+    pub memo_input_height: f32,
+    pub always_on_top: bool,
+    pub new_memo_text: String,
+    pub window_width: f32,
+    pub window_height: f32,
+    pub window_x: Option<f32>,
+    pub window_y: Option<f32>,
+    pub workspace_id: i32, // Workspace shown on startup and used by the CLI
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -67,4 +191,5 @@ pub enum ActiveTab {
     Cold,
     Done,
     Delayed,
+    Review,
 }
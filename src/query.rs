@@ -0,0 +1,366 @@
+// Search query language shared by the tab search boxes and the global search.
+//
+//   groceries            memos containing a word starting with "groceries"
+//   "exact phrase"       memos containing the phrase
+//   title:foo body:bar   restrict a word or phrase to the title or body
+//   -word                memos NOT containing the word (also -title:, -status:, ...)
+//   status:cold,done     memos with one of the given statuses
+//   tag:work or #work    memos tagged work (or a sub-tag like work/project)
+//   created:<2026-01-01  memos created before a date (also <=, >, >=, =)
+//
+// All terms must match. Positive text terms are answered by the FTS index,
+// everything else is checked in memory by `Query::matches`.
+
+use chrono::{Local, NaiveDate};
+
+use crate::models::{MemoData, MemoStatus};
+use crate::tags;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Any,
+    Title,
+    Body,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Before,
+    OnOrBefore,
+    On,
+    OnOrAfter,
+    After,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Term {
+    Text {
+        field: Field,
+        text: String,
+        phrase: bool,
+    },
+    Status(Vec<MemoStatus>),
+    Tag(String),
+    Created(Comparison, NaiveDate),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    pub term: Term,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Query {
+    pub filters: Vec<Filter>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut filters = Vec::new();
+        let mut rest = input;
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+
+            let negated = rest.starts_with('-') && rest.len() > 1;
+            if negated {
+                rest = &rest[1..];
+            }
+
+            // Optional `key:` prefix, or `#` for tags
+            let mut key = None;
+            if rest.starts_with('#') {
+                key = Some("tag");
+                rest = &rest[1..];
+            } else if let Some(colon) = rest.find(':') {
+                let candidate = &rest[..colon];
+                if matches!(candidate, "title" | "body" | "status" | "tag" | "created") {
+                    key = Some(candidate);
+                    rest = &rest[colon + 1..];
+                }
+            }
+
+            let (value, phrase, remaining) = take_value(rest);
+            rest = remaining;
+
+            let term = match key {
+                Some("status") => Term::Status(parse_statuses(&value)?),
+                Some("tag") => {
+                    let tag = value.trim_start_matches('#').to_lowercase();
+                    if tag.is_empty() {
+                        return Err("tag: needs a value".to_string());
+                    }
+                    Term::Tag(tag)
+                }
+                Some("created") => {
+                    let (comparison, date) = parse_date_comparison(&value)?;
+                    Term::Created(comparison, date)
+                }
+                Some("title") => Term::Text {
+                    field: Field::Title,
+                    text: value,
+                    phrase,
+                },
+                Some("body") => Term::Text {
+                    field: Field::Body,
+                    text: value,
+                    phrase,
+                },
+                _ => Term::Text {
+                    field: Field::Any,
+                    text: value,
+                    phrase,
+                },
+            };
+
+            // Skip text the search index would drop entirely (e.g. a lone "-")
+            if let Term::Text { text, .. } = &term
+                && !text.chars().any(char::is_alphanumeric)
+            {
+                continue;
+            }
+
+            filters.push(Filter { term, negated });
+        }
+
+        Ok(Query { filters })
+    }
+
+    // FTS5 query for the positive text terms, or None if there are none.
+    // Every term is quoted so that FTS5 operators in the input are plain text.
+    pub fn fts_query(&self) -> Option<String> {
+        let terms: Vec<String> = self
+            .filters
+            .iter()
+            .filter(|filter| !filter.negated)
+            .filter_map(|filter| match &filter.term {
+                Term::Text {
+                    field,
+                    text,
+                    phrase,
+                } => {
+                    let column = match field {
+                        Field::Any => "",
+                        Field::Title => "title : ",
+                        Field::Body => "body : ",
+                    };
+                    let prefix = if *phrase { "" } else { "*" };
+                    Some(format!(
+                        "{}\"{}\"{}",
+                        column,
+                        text.replace('"', "\"\""),
+                        prefix
+                    ))
+                }
+                _ => None,
+            })
+            .collect();
+
+        if terms.is_empty() {
+            None
+        } else {
+            Some(terms.join(" "))
+        }
+    }
+
+    // Checks the terms the FTS index does not cover: statuses, tags, dates
+    // and negated text. Positive text terms are assumed to have matched already.
+    pub fn matches(&self, memo: &MemoData) -> bool {
+        self.filters.iter().all(|filter| {
+            let matched = match &filter.term {
+                Term::Text { .. } if !filter.negated => return true,
+                term => term_matches(memo, term),
+            };
+            matched != filter.negated
+        })
+    }
+}
+
+// Reads a quoted phrase or a bare word; returns it, whether it was quoted,
+// and the remaining input
+fn take_value(input: &str) -> (String, bool, &str) {
+    if let Some(quoted) = input.strip_prefix('"') {
+        // Phrase, up to the closing quote or the end of the input
+        let end = quoted.find('"').unwrap_or(quoted.len());
+        let rest = quoted.get(end + 1..).unwrap_or("");
+        (quoted[..end].to_string(), true, rest)
+    } else {
+        let end = input.find(char::is_whitespace).unwrap_or(input.len());
+        (input[..end].to_string(), false, &input[end..])
+    }
+}
+
+fn parse_statuses(value: &str) -> Result<Vec<MemoStatus>, String> {
+    value
+        .split(',')
+        .filter(|status| !status.is_empty())
+        .map(|status| match status.to_lowercase().as_str() {
+            "hot" => Ok(MemoStatus::Hot),
+            "cold" => Ok(MemoStatus::Cold),
+            "done" => Ok(MemoStatus::Done),
+            "delayed" => Ok(MemoStatus::Delayed),
+            other => Err(format!("unknown status '{}'", other)),
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .and_then(|statuses| {
+            if statuses.is_empty() {
+                Err("status: needs a value".to_string())
+            } else {
+                Ok(statuses)
+            }
+        })
+}
+
+fn parse_date_comparison(value: &str) -> Result<(Comparison, NaiveDate), String> {
+    let (comparison, date) = if let Some(date) = value.strip_prefix("<=") {
+        (Comparison::OnOrBefore, date)
+    } else if let Some(date) = value.strip_prefix(">=") {
+        (Comparison::OnOrAfter, date)
+    } else if let Some(date) = value.strip_prefix('<') {
+        (Comparison::Before, date)
+    } else if let Some(date) = value.strip_prefix('>') {
+        (Comparison::After, date)
+    } else {
+        (Comparison::On, value.strip_prefix('=').unwrap_or(value))
+    };
+
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date '{}', expected YYYY-MM-DD", date))?;
+    Ok((comparison, date))
+}
+
+fn text_matches(memo: &MemoData, field: Field, text: &str) -> bool {
+    let text = text.to_lowercase();
+    let in_title = || memo.title.to_lowercase().contains(&text);
+    let in_body = || memo.body.to_lowercase().contains(&text);
+    match field {
+        Field::Any => in_title() || in_body(),
+        Field::Title => in_title(),
+        Field::Body => in_body(),
+    }
+}
+
+fn term_matches(memo: &MemoData, term: &Term) -> bool {
+    match term {
+        Term::Text { field, text, .. } => text_matches(memo, *field, text),
+        Term::Status(statuses) => statuses.contains(&memo.status),
+        Term::Tag(tag) => tags::has_tag(&memo.tags, tag),
+        Term::Created(comparison, date) => {
+            // Compare calendar days in local time, as shown in the UI
+            let created = memo.creation_date.with_timezone(&Local).date_naive();
+            match comparison {
+                Comparison::Before => created < *date,
+                Comparison::OnOrBefore => created <= *date,
+                Comparison::On => created == *date,
+                Comparison::OnOrAfter => created >= *date,
+                Comparison::After => created > *date,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(term: Term, negated: bool) -> Filter {
+        Filter { term, negated }
+    }
+
+    #[test]
+    fn parses_terms() {
+        let query = Query::parse(
+            r#"milk "oat bread" -title:eggs status:cold,done #work created:>=2026-01-01"#,
+        )
+        .unwrap();
+        assert_eq!(
+            query.filters,
+            vec![
+                term(
+                    Term::Text {
+                        field: Field::Any,
+                        text: "milk".to_string(),
+                        phrase: false
+                    },
+                    false
+                ),
+                term(
+                    Term::Text {
+                        field: Field::Any,
+                        text: "oat bread".to_string(),
+                        phrase: true
+                    },
+                    false
+                ),
+                term(
+                    Term::Text {
+                        field: Field::Title,
+                        text: "eggs".to_string(),
+                        phrase: false
+                    },
+                    true
+                ),
+                term(
+                    Term::Status(vec![MemoStatus::Cold, MemoStatus::Done]),
+                    false
+                ),
+                term(Term::Tag("work".to_string()), false),
+                term(
+                    Term::Created(
+                        Comparison::OnOrAfter,
+                        NaiveDate::from_ymd_opt(2026, 1, 1).unwrap()
+                    ),
+                    false
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn fts_query_quotes_positive_text_terms() {
+        let query = Query::parse(r#"a"b title:"x y" -skip OR"#).unwrap();
+        assert_eq!(
+            query.fts_query().as_deref(),
+            Some(r#""a""b"* title : "x y" "OR"*"#)
+        );
+        assert_eq!(Query::parse("status:hot -word").unwrap().fts_query(), None);
+    }
+
+    #[test]
+    fn skips_text_without_words() {
+        assert_eq!(Query::parse(" - ... \"\" ").unwrap(), Query::default());
+    }
+
+    #[test]
+    fn unknown_keys_are_text() {
+        let query = Query::parse("http://example.com").unwrap();
+        assert!(matches!(
+            &query.filters[0].term,
+            Term::Text { field: Field::Any, text, .. } if text == "http://example.com"
+        ));
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let error = |input| Query::parse(input).unwrap_err();
+        assert_eq!(error("status:frozen"), "unknown status 'frozen'");
+        assert_eq!(error("status:"), "status: needs a value");
+        assert_eq!(error("status:,"), "status: needs a value");
+        assert_eq!(error("-status:hot,nope"), "unknown status 'nope'");
+        assert_eq!(error("tag:"), "tag: needs a value");
+        assert_eq!(error("#"), "tag: needs a value");
+        assert_eq!(error("tag:#"), "tag: needs a value");
+        assert_eq!(
+            error("created:2026-13-01"),
+            "invalid date '2026-13-01', expected YYYY-MM-DD"
+        );
+        assert_eq!(
+            error("created:<yesterday"),
+            "invalid date 'yesterday', expected YYYY-MM-DD"
+        );
+        assert_eq!(error("created:"), "invalid date '', expected YYYY-MM-DD");
+    }
+}
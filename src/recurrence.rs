@@ -0,0 +1,474 @@
+// Recurrence rules for routine memos. When a recurring memo is moved to done,
+// its next occurrence is created in Delayed at the rule's next fire time and
+// the rule moves to the new memo.
+//
+//   daily [time]                   every day, e.g. "daily 8:30"
+//   weekdays [time]                Monday to Friday
+//   weekly <days> [time]           e.g. "weekly mon,thu 14:00"
+//   monthly <day> [time]           e.g. "monthly 1"; months without the day are skipped
+//   cron <min hour dom month dow>  a 5-field cron expression, e.g. "cron 0 9 * * mon"
+//
+// The time defaults to `schedule::MORNING_HOUR`. Rules are stored as this text.
+
+use std::fmt;
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, Timelike, Utc, Weekday};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::schedule;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Recurrence {
+    Daily(NaiveTime),
+    Weekdays(NaiveTime),
+    Weekly(Vec<Weekday>, NaiveTime),
+    Monthly(u32, NaiveTime),
+    Cron(CronSchedule),
+}
+
+impl Recurrence {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim().to_lowercase();
+        let words: Vec<&str> = input.split_whitespace().collect();
+        let Some((&kind, rest)) = words.split_first() else {
+            return Err("no repeat rule given".to_string());
+        };
+
+        match kind {
+            "daily" => Ok(Recurrence::Daily(parse_at(rest)?)),
+            "weekdays" => Ok(Recurrence::Weekdays(parse_at(rest)?)),
+            "weekly" => {
+                let (days, rest) = rest
+                    .split_first()
+                    .ok_or("weekly needs days, e.g. weekly mon,thu")?;
+                let mut days = days
+                    .split(',')
+                    .filter(|day| !day.is_empty())
+                    .map(|day| {
+                        day.parse::<Weekday>()
+                            .map_err(|_| format!("unknown day '{}'", day))
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if days.is_empty() {
+                    return Err("weekly needs at least one day".to_string());
+                }
+                days.sort_by_key(|day| day.num_days_from_monday());
+                days.dedup();
+                Ok(Recurrence::Weekly(days, parse_at(rest)?))
+            }
+            "monthly" => {
+                let (day, rest) = rest
+                    .split_first()
+                    .ok_or("monthly needs a day of the month, e.g. monthly 1")?;
+                let day = day
+                    .trim_end_matches(|c: char| c.is_ascii_alphabetic()) // "1st", "22nd"
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|day| (1..=31).contains(day))
+                    .ok_or_else(|| format!("invalid day of the month '{}'", day))?;
+                Ok(Recurrence::Monthly(day, parse_at(rest)?))
+            }
+            "cron" => Ok(Recurrence::Cron(CronSchedule::parse(&rest.join(" "))?)),
+            _ if words.len() == 5 => Ok(Recurrence::Cron(CronSchedule::parse(&input)?)),
+            _ => Err(format!(
+                "unknown repeat rule '{}' (daily, weekdays, weekly, monthly or cron)",
+                input
+            )),
+        }
+    }
+
+    // First fire time strictly after the given time, or None if the rule
+    // never fires (e.g. "cron 0 9 30 2 *")
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Utc>> {
+        match self {
+            Recurrence::Cron(cron) => cron.next_after(after),
+            _ => CronSchedule::parse(&self.cron_expression())
+                .ok()?
+                .next_after(after),
+        }
+    }
+
+    fn cron_expression(&self) -> String {
+        let at = |time: &NaiveTime| format!("{} {}", time.minute(), time.hour());
+        match self {
+            Recurrence::Daily(time) => format!("{} * * *", at(time)),
+            Recurrence::Weekdays(time) => format!("{} * * 1-5", at(time)),
+            Recurrence::Weekly(days, time) => {
+                let days: Vec<String> = days
+                    .iter()
+                    .map(|day| day.num_days_from_sunday().to_string())
+                    .collect();
+                format!("{} * * {}", at(time), days.join(","))
+            }
+            Recurrence::Monthly(day, time) => format!("{} {} * *", at(time), day),
+            Recurrence::Cron(cron) => cron.expression.clone(),
+        }
+    }
+
+    // Human-readable summary, e.g. "Every Mon, Thu at 09:00"
+    pub fn describe(&self) -> String {
+        let at = |time: &NaiveTime| time.format("%H:%M");
+        match self {
+            Recurrence::Daily(time) => format!("Every day at {}", at(time)),
+            Recurrence::Weekdays(time) => format!("Weekdays at {}", at(time)),
+            Recurrence::Weekly(days, time) => {
+                let days: Vec<String> = days.iter().map(|day| day.to_string()).collect();
+                format!("Every {} at {}", days.join(", "), at(time))
+            }
+            Recurrence::Monthly(day, time) => {
+                format!("Monthly on day {} at {}", day, at(time))
+            }
+            Recurrence::Cron(cron) => format!("Cron {}", cron.expression),
+        }
+    }
+}
+
+// The stored form, which `Recurrence::parse` reads back
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let at = |time: &NaiveTime| time.format("%H:%M");
+        match self {
+            Recurrence::Daily(time) => write!(f, "daily {}", at(time)),
+            Recurrence::Weekdays(time) => write!(f, "weekdays {}", at(time)),
+            Recurrence::Weekly(days, time) => {
+                let days: Vec<String> = days
+                    .iter()
+                    .map(|day| day.to_string().to_lowercase())
+                    .collect();
+                write!(f, "weekly {} {}", days.join(","), at(time))
+            }
+            Recurrence::Monthly(day, time) => write!(f, "monthly {} {}", day, at(time)),
+            Recurrence::Cron(cron) => write!(f, "cron {}", cron.expression),
+        }
+    }
+}
+
+impl Serialize for Recurrence {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Recurrence {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Recurrence::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+// Optional "[at] <time>" after a rule
+fn parse_at(words: &[&str]) -> Result<NaiveTime, String> {
+    let words = match words.split_first() {
+        Some((&"at", rest)) => rest,
+        _ => words,
+    };
+    if words.is_empty() {
+        return Ok(NaiveTime::from_hms_opt(schedule::MORNING_HOUR, 0, 0).unwrap_or_default());
+    }
+    schedule::parse_time(&words.concat())
+        .ok_or_else(|| format!("invalid time '{}'", words.join(" ")))
+}
+
+// A parsed 5-field cron expression. Each field is a bit set of the values it
+// allows. As in cron, when both day of month and day of week are restricted,
+// a day matching either fires.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64, // Sunday = 0
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+// Cron expressions such as Feb 30 never fire; give up after this many days
+const MAX_SEARCH_DAYS: u32 = 366 * 5;
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..] else {
+            return Err(format!(
+                "cron needs 5 fields (minute hour day month weekday), got '{}'",
+                expression
+            ));
+        };
+
+        let mut days_of_week = parse_field(day_of_week, 0, 7)?;
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1; // 7 is also Sunday
+        }
+
+        Ok(Self {
+            expression: fields.join(" "),
+            minutes: parse_field(minute, 0, 59)?,
+            hours: parse_field(hour, 0, 23)?,
+            days_of_month: parse_field(day_of_month, 1, 31)?,
+            months: parse_field(month, 1, 12)?,
+            days_of_week,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let allows = |bits: u64, value: u32| bits & (1 << value) != 0;
+        if !allows(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = allows(self.days_of_month, date.day());
+        let day_of_week = allows(self.days_of_week, date.weekday().num_days_from_sunday());
+        if self.any_day_of_month || self.any_day_of_week {
+            day_of_month && day_of_week
+        } else {
+            day_of_month || day_of_week
+        }
+    }
+
+    pub fn next_after(&self, after: DateTime<Local>) -> Option<DateTime<Utc>> {
+        let mut date = after.date_naive();
+        for _ in 0..MAX_SEARCH_DAYS {
+            if self.matches_day(date) {
+                for hour in (0..24).filter(|hour| self.hours & (1 << hour) != 0) {
+                    for minute in (0..60).filter(|minute| self.minutes & (1 << minute) != 0) {
+                        let at = schedule::local_to_utc(date.and_hms_opt(hour, minute, 0)?);
+                        if at > after {
+                            return Some(at);
+                        }
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+}
+
+// Parses one cron field: "*", "5", "1-5", "*/15", "0-30/10" or a comma list
+// of those. Days of the week may also be names ("mon").
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, String> {
+    let invalid = || format!("invalid cron field '{}'", field);
+    let value = |text: &str| {
+        text.parse::<u32>()
+            .ok()
+            .or_else(|| {
+                // Weekday names, only valid in the day-of-week field
+                (max == 7)
+                    .then(|| text.parse::<Weekday>().ok())
+                    .flatten()
+                    .map(|day| day.num_days_from_sunday())
+            })
+            .ok_or_else(invalid)
+    };
+
+    let mut bits = 0u64;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|&step| step > 0)
+                    .ok_or_else(invalid)?,
+            ),
+            None => (item, 1),
+        };
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // "5/15" means every 15 starting at 5
+            (start, if step > 1 { max } else { start })
+        };
+        if start < min || end > max || start > end {
+            return Err(invalid());
+        }
+        for value in (start..=end).step_by(step as usize) {
+            bits |= 1 << value;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::tests::{local, utc};
+
+    fn next(rule: &str, after: &str) -> Option<DateTime<Utc>> {
+        Recurrence::parse(rule).unwrap().next_after(local(after))
+    }
+
+    #[test]
+    fn parses_rules() {
+        let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert_eq!(Recurrence::parse("daily"), Ok(Recurrence::Daily(nine)));
+        assert_eq!(
+            Recurrence::parse("Weekdays at 8:30"),
+            Ok(Recurrence::Weekdays(at(8, 30)))
+        );
+        assert_eq!(
+            Recurrence::parse("weekly thu,mon,thu 2pm"),
+            Ok(Recurrence::Weekly(
+                vec![Weekday::Mon, Weekday::Thu],
+                at(14, 0)
+            ))
+        );
+        assert_eq!(
+            Recurrence::parse("monthly 22nd"),
+            Ok(Recurrence::Monthly(22, nine))
+        );
+        assert!(matches!(
+            Recurrence::parse("0 9 * * mon"),
+            Ok(Recurrence::Cron(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_rules() {
+        for rule in [
+            "",
+            "hourly",
+            "daily 25:00",
+            "weekly",
+            "weekly funday",
+            "monthly",
+            "monthly 32",
+            "cron 0 9 * *",
+            "cron 60 9 * * *",
+            "cron 0 24 * * *",
+            "cron 0 9 0 * *",
+            "cron 0 9 * 13 *",
+            "cron 0 9 * * 8",
+            "cron */0 * * * *",
+            "cron 5-1 * * * *",
+            "cron 0 9 * jan *",
+        ] {
+            assert!(Recurrence::parse(rule).is_err(), "{:?}", rule);
+        }
+    }
+
+    #[test]
+    fn weekly_needs_a_day() {
+        for rule in ["weekly ,", "weekly ,,", "weekly , 9am"] {
+            assert_eq!(
+                Recurrence::parse(rule),
+                Err("weekly needs at least one day".to_string())
+            );
+        }
+    }
+
+    #[test]
+    fn stored_form_parses_back() {
+        for rule in [
+            "daily 08:30",
+            "weekdays 09:00",
+            "weekly mon,thu 14:00",
+            "monthly 1 09:00",
+            "cron */15 9-17 * * 1-5",
+        ] {
+            let recurrence = Recurrence::parse(rule).unwrap();
+            assert_eq!(recurrence.to_string(), rule);
+            assert_eq!(Recurrence::parse(&recurrence.to_string()), Ok(recurrence));
+        }
+    }
+
+    #[test]
+    fn next_after_crosses_month_and_year_ends() {
+        assert_eq!(
+            next("daily 8:30", "2026-10-31 09:00"),
+            Some(utc("2026-11-01 07:30"))
+        );
+        assert_eq!(
+            next("monthly 1", "2026-12-15 12:00"),
+            Some(utc("2027-01-01 08:00"))
+        );
+        // November has no 31st
+        assert_eq!(
+            next("monthly 31", "2026-10-31 10:00"),
+            Some(utc("2026-12-31 08:00"))
+        );
+        assert_eq!(
+            next("weekdays", "2026-10-30 10:00"),
+            Some(utc("2026-11-02 08:00"))
+        );
+    }
+
+    #[test]
+    fn next_after_is_strictly_later() {
+        assert_eq!(
+            next("daily 9:00", "2026-10-16 09:00"),
+            Some(utc("2026-10-17 07:00"))
+        );
+        assert_eq!(
+            next("cron */15 * * * *", "2026-10-16 09:00"),
+            Some(utc("2026-10-16 07:15"))
+        );
+    }
+
+    #[test]
+    fn next_after_keeps_wall_clock_time_across_dst() {
+        // Summer time ends on 2026-10-25 (UTC+2 to UTC+1)
+        assert_eq!(
+            next("daily 9:00", "2026-10-24 10:00"),
+            Some(utc("2026-10-25 08:00"))
+        );
+        // and starts on 2026-03-29 (UTC+1 to UTC+2)
+        assert_eq!(
+            next("weekly sun", "2026-03-22 10:00"),
+            Some(utc("2026-03-29 07:00"))
+        );
+    }
+
+    #[test]
+    fn next_after_handles_skipped_and_repeated_hours() {
+        // 02:30 is skipped on 2026-03-29 and runs at 03:30 summer time
+        assert_eq!(
+            next("cron 30 2 * * *", "2026-03-28 03:00"),
+            Some(utc("2026-03-29 01:30"))
+        );
+        // 02:30 happens twice on 2026-10-25; the first one counts
+        assert_eq!(
+            next("cron 30 2 * * *", "2026-10-24 03:00"),
+            Some(utc("2026-10-25 00:30"))
+        );
+    }
+
+    #[test]
+    fn next_after_handles_leap_days() {
+        assert_eq!(
+            next("cron 0 9 29 2 *", "2026-03-01 00:00"),
+            Some(utc("2028-02-29 08:00"))
+        );
+        assert_eq!(
+            next("daily", "2028-02-28 10:00"),
+            Some(utc("2028-02-29 08:00"))
+        );
+        // Never fires
+        assert_eq!(next("cron 0 9 30 2 *", "2026-03-01 00:00"), None);
+    }
+
+    #[test]
+    fn cron_day_fields_match_either_when_both_restricted() {
+        // The 13th or any Friday, from a Friday morning
+        assert_eq!(
+            next("cron 0 9 13 * fri", "2026-10-16 10:00"),
+            Some(utc("2026-10-23 07:00"))
+        );
+        assert_eq!(
+            next("cron 0 9 17 * fri", "2026-10-16 10:00"),
+            Some(utc("2026-10-17 07:00"))
+        );
+        // 7 is also Sunday
+        assert_eq!(
+            next("cron 0 9 * * 7", "2026-10-16 10:00"),
+            Some(utc("2026-10-18 07:00"))
+        );
+    }
+}
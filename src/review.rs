@@ -0,0 +1,143 @@
+// SM-2 style scheduling for the cold memo review session. Each cold memo has
+// an interval (days until its next review) and an ease factor that scales the
+// interval every time the memo is kept. Memos never reviewed are due now.
+
+use chrono::{DateTime, Duration, Utc};
+
+pub const DEFAULT_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+const EASE_STEP_KEEP: f64 = 0.1;
+const EASE_STEP_PROMOTE: f64 = 0.2;
+const MAX_INTERVAL_DAYS: u32 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReviewAnswer {
+    Promote, // Move to hot; the schedule starts over if it comes back to cold
+    Keep,    // Still relevant, keep cold and review again later
+    Done,    // Move to done
+    Drop,    // No longer relevant; move to done without repeating
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReviewSchedule {
+    pub due_at: Option<DateTime<Utc>>, // None = never reviewed, due now
+    pub interval_days: u32,
+    pub ease: f64,
+}
+
+impl Default for ReviewSchedule {
+    fn default() -> Self {
+        Self {
+            due_at: None,
+            interval_days: 0,
+            ease: DEFAULT_EASE,
+        }
+    }
+}
+
+impl ReviewSchedule {
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        self.due_at.is_none_or(|due_at| due_at <= now)
+    }
+
+    // The schedule after answering a review. Keeping a memo cold makes its
+    // intervals grow faster; promoting it means it was needed sooner than
+    // expected, so it starts over with slower growth the next time it goes
+    // cold. Done and Drop take the memo out of cold for good.
+    pub fn answer(&self, answer: ReviewAnswer, now: DateTime<Utc>) -> Self {
+        match answer {
+            ReviewAnswer::Keep => {
+                let interval_days = self.next_interval().min(MAX_INTERVAL_DAYS);
+                Self {
+                    due_at: Some(now + Duration::days(interval_days as i64)),
+                    interval_days,
+                    ease: self.ease + EASE_STEP_KEEP,
+                }
+            }
+            ReviewAnswer::Promote => Self {
+                ease: (self.ease - EASE_STEP_PROMOTE).max(MIN_EASE),
+                ..Self::default()
+            },
+            ReviewAnswer::Done | ReviewAnswer::Drop => Self::default(),
+        }
+    }
+
+    // 1 day, then 6 days, then the previous interval times the ease
+    fn next_interval(&self) -> u32 {
+        match self.interval_days {
+            0 => 1,
+            1 => 6,
+            days => (days as f64 * self.ease).round() as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schedule::tests::utc;
+
+    #[test]
+    fn keeping_grows_the_interval_and_ease() {
+        let now = utc("2026-10-16 12:00");
+        let first = ReviewSchedule::default().answer(ReviewAnswer::Keep, now);
+        assert_eq!(first.interval_days, 1);
+        assert_eq!(first.due_at, Some(utc("2026-10-17 12:00")));
+        assert!(first.ease > DEFAULT_EASE);
+
+        let second = first.answer(ReviewAnswer::Keep, now);
+        assert_eq!(second.interval_days, 6);
+        let third = second.answer(ReviewAnswer::Keep, now);
+        assert_eq!(third.interval_days, (6.0 * second.ease).round() as u32);
+        assert!(third.ease > second.ease);
+    }
+
+    #[test]
+    fn caps_the_interval() {
+        let schedule = ReviewSchedule {
+            due_at: None,
+            interval_days: 300,
+            ease: 2.5,
+        };
+        let next = schedule.answer(ReviewAnswer::Keep, utc("2026-10-16 12:00"));
+        assert_eq!(next.interval_days, MAX_INTERVAL_DAYS);
+    }
+
+    #[test]
+    fn promoting_starts_over_with_lower_ease() {
+        let schedule = ReviewSchedule {
+            due_at: Some(utc("2026-10-16 12:00")),
+            interval_days: 6,
+            ease: 2.5,
+        };
+        let next = schedule.answer(ReviewAnswer::Promote, utc("2026-10-16 12:00"));
+        assert_eq!(next.due_at, None);
+        assert_eq!(next.interval_days, 0);
+        assert!(next.ease < schedule.ease);
+
+        // Never below the minimum
+        let mut schedule = schedule;
+        for _ in 0..20 {
+            schedule = schedule.answer(ReviewAnswer::Promote, utc("2026-10-16 12:00"));
+        }
+        assert_eq!(schedule.ease, MIN_EASE);
+    }
+
+    #[test]
+    fn done_and_drop_reset_the_schedule() {
+        let schedule = ReviewSchedule {
+            due_at: Some(utc("2026-10-16 12:00")),
+            interval_days: 6,
+            ease: 1.5,
+        };
+        let now = utc("2026-10-16 12:00");
+        assert_eq!(
+            schedule.answer(ReviewAnswer::Done, now),
+            ReviewSchedule::default()
+        );
+        assert_eq!(
+            schedule.answer(ReviewAnswer::Drop, now),
+            ReviewSchedule::default()
+        );
+    }
+}
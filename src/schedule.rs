@@ -0,0 +1,474 @@
+// Helpers for working out when snoozed and delayed memos wake up. Wake-up
+// times are stored as absolute UTC timestamps; the presets here are computed
+// in local time.
+
+use chrono::{
+    DateTime, Datelike, Days, Duration, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime,
+    TimeZone, Utc, Weekday,
+};
+
+// Hour that "morning" presets wake up at
+pub const MORNING_HOUR: u32 = 9;
+
+// Converts a local wall-clock time to UTC. Times repeated by a DST change
+// resolve to the first of the two, and skipped times to an hour later.
+pub fn local_to_utc(local: NaiveDateTime) -> DateTime<Utc> {
+    // `LocalResult::earliest` does not always return the earlier instant for
+    // `Local`, so compare them in UTC
+    let first = |local: &NaiveDateTime| match Local.from_local_datetime(local) {
+        LocalResult::Single(dt) => Some(dt.with_timezone(&Utc)),
+        LocalResult::Ambiguous(a, b) => Some(a.with_timezone(&Utc).min(b.with_timezone(&Utc))),
+        LocalResult::None => None,
+    };
+    first(&local)
+        .or_else(|| first(&local.checked_add_signed(Duration::hours(1))?))
+        .unwrap_or_else(|| local.and_utc())
+}
+
+fn morning() -> NaiveTime {
+    NaiveTime::from_hms_opt(MORNING_HOUR, 0, 0).unwrap_or_default()
+}
+
+fn morning_of(date: NaiveDate) -> DateTime<Utc> {
+    local_to_utc(date.and_time(morning()))
+}
+
+pub fn tomorrow_morning(now: DateTime<Local>) -> DateTime<Utc> {
+    let tomorrow = now.date_naive() + Days::new(1);
+    morning_of(tomorrow)
+}
+
+// The coming Monday (a week ahead if today is Monday)
+pub fn next_monday_morning(now: DateTime<Local>) -> DateTime<Utc> {
+    let days_ahead = 7 - now.weekday().num_days_from_monday() as u64;
+    morning_of(now.date_naive() + Days::new(days_ahead))
+}
+
+// Short local description of a wake-up time, e.g. "14:30", "Tue 09:00" or
+// "2026-11-02 08:30"
+pub fn format_wake_time(at: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let local = at.with_timezone(&Local);
+    let today = now.with_timezone(&Local).date_naive();
+    let days = (local.date_naive() - today).num_days();
+    if days == 0 {
+        local.format("%H:%M").to_string()
+    } else if (1..7).contains(&days) {
+        local.format("%a %H:%M").to_string()
+    } else {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+const TOO_LARGE: &str = "duration too large";
+
+// Adds `amount` of a unit built by one of the `Duration::try_*` constructors
+fn add_amount(
+    total: Duration,
+    amount: i64,
+    unit: fn(i64) -> Option<Duration>,
+) -> Result<Duration, String> {
+    unit(amount)
+        .and_then(|amount| total.checked_add(&amount))
+        .ok_or_else(|| TOO_LARGE.to_string())
+}
+
+// Parses durations like "2h", "30m", "1d", "1h30m" or "HH:MM"
+pub fn parse_duration(value: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid duration '{}'", value);
+
+    if let Some((hours, minutes)) = value.split_once(':') {
+        let hours: i64 = hours.parse().map_err(|_| invalid())?;
+        let minutes: i64 = minutes.parse().map_err(|_| invalid())?;
+        if hours < 0 || minutes < 0 {
+            return Err(invalid());
+        }
+        let total = add_amount(Duration::zero(), hours, Duration::try_hours)?;
+        let total = add_amount(total, minutes, Duration::try_minutes)?;
+        if total <= Duration::zero() {
+            return Err(invalid());
+        }
+        return Ok(total);
+    }
+
+    let mut total = Duration::zero();
+    let mut number = String::new();
+    for c in value.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let amount: i64 = number.parse().map_err(|_| invalid())?;
+        number.clear();
+        let unit = match c {
+            'w' => Duration::try_weeks,
+            'd' => Duration::try_days,
+            'h' => Duration::try_hours,
+            'm' => Duration::try_minutes,
+            _ => return Err(invalid()),
+        };
+        total = add_amount(total, amount, unit)?;
+    }
+
+    // A bare number is minutes
+    if !number.is_empty() {
+        let amount: i64 = number.parse().map_err(|_| invalid())?;
+        total = add_amount(total, amount, Duration::try_minutes)?;
+    }
+
+    if total <= Duration::zero() {
+        return Err(invalid());
+    }
+    Ok(total)
+}
+
+// Parses when a memo should wake up. Accepts durations from now ("2h",
+// "1h30m", "01:30", "in 3 days", "in an hour"), days with an optional time
+// ("tomorrow", "tomorrow 9am", "fri 14:00", "next monday", "tonight"), a time
+// of day ("at 5pm", "9:30am"), and ISO dates and times ("2026-11-02",
+// "2026-11-02 08:30", RFC 3339). Times in the past are rejected.
+pub fn parse_wake_time(input: &str, now: DateTime<Local>) -> Result<DateTime<Utc>, String> {
+    let original = input.trim();
+    let input = original.to_lowercase();
+    let invalid = || format!("don't understand '{}'", original);
+    if input.is_empty() {
+        return Err("no time given".to_string());
+    }
+
+    let future = |at: DateTime<Utc>| {
+        if at > now {
+            Ok(at)
+        } else {
+            Err(format!(
+                "{} is in the past",
+                at.with_timezone(&Local).format("%Y-%m-%d %H:%M")
+            ))
+        }
+    };
+
+    // Full timestamps
+    if let Ok(at) = DateTime::parse_from_rfc3339(original) {
+        return future(at.with_timezone(&Utc));
+    }
+    for format in [
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+    ] {
+        if let Ok(local) = NaiveDateTime::parse_from_str(original, format) {
+            return future(local_to_utc(local));
+        }
+    }
+
+    // Durations from now
+    let relative = input.strip_prefix("in ").unwrap_or(&input);
+    if let Some(duration) = parse_spoken_duration(relative)? {
+        return now
+            .with_timezone(&Utc)
+            .checked_add_signed(duration)
+            .ok_or_else(|| TOO_LARGE.to_string());
+    }
+
+    // An optional day followed by an optional time
+    let mut words: Vec<&str> = input.split_whitespace().collect();
+    let next = words.first() == Some(&"next");
+    if next {
+        words.remove(0);
+    }
+    let today = now.date_naive();
+    let day = words.first().and_then(|word| parse_day(word, today, next));
+    if next && !day.as_ref().is_some_and(|day| day.weekly) {
+        // Only "next <weekday>"
+        return Err(invalid());
+    }
+    let time_words = if day.is_some() {
+        &words[1..]
+    } else {
+        &words[..]
+    };
+    let time_words = match time_words.split_first() {
+        Some((&"at", rest)) => rest,
+        _ => time_words,
+    };
+    let time = if time_words.is_empty() {
+        None
+    } else {
+        Some(parse_time(&time_words.concat()).ok_or_else(invalid)?)
+    };
+
+    match (day, time) {
+        (Some(day), time) => {
+            let time = time.unwrap_or(day.default_time);
+            let at = local_to_utc(day.date.and_time(time));
+            if at <= now && day.weekly {
+                // e.g. "fri 9am" on a Friday afternoon means next week, at
+                // the same wall-clock time even across a DST change
+                Ok(local_to_utc((day.date + Days::new(7)).and_time(time)))
+            } else {
+                future(at)
+            }
+        }
+        (None, Some(time)) => {
+            // A bare time is the next time the clock shows it
+            let at = local_to_utc(today.and_time(time));
+            if at > now {
+                Ok(at)
+            } else {
+                Ok(local_to_utc((today + Days::new(1)).and_time(time)))
+            }
+        }
+        (None, None) => Err(invalid()),
+    }
+}
+
+struct Day {
+    date: NaiveDate,
+    default_time: NaiveTime, // Used when no time is given
+    weekly: bool,            // A weekday name, so a past time means next week
+}
+
+// With `next`, a weekday is the one at least a week from today
+fn parse_day(word: &str, today: NaiveDate, next: bool) -> Option<Day> {
+    let day = |date, default_time, weekly| {
+        Some(Day {
+            date,
+            default_time,
+            weekly,
+        })
+    };
+    match word {
+        "today" => day(today, morning(), false),
+        "tonight" => day(today, NaiveTime::from_hms_opt(20, 0, 0)?, false),
+        "tomorrow" | "tmrw" | "tmr" => day(today + Days::new(1), morning(), false),
+        _ => {
+            if let Ok(date) = NaiveDate::parse_from_str(word, "%Y-%m-%d") {
+                return day(date, morning(), false);
+            }
+            let weekday: Weekday = word.parse().ok()?;
+            let mut days_ahead =
+                (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+            if next {
+                days_ahead += 7;
+            }
+            day(today + Days::new(days_ahead as u64), morning(), true)
+        }
+    }
+}
+
+// Parses "9am", "9:30 pm", "14:00", "14", "noon", "morning" or "evening"
+pub fn parse_time(text: &str) -> Option<NaiveTime> {
+    match text {
+        "noon" => return NaiveTime::from_hms_opt(12, 0, 0),
+        "morning" => return Some(morning()),
+        "evening" => return NaiveTime::from_hms_opt(18, 0, 0),
+        _ => {}
+    }
+
+    let (clock, meridiem) = if let Some(clock) = text.strip_suffix("am") {
+        (clock, Some(false))
+    } else if let Some(clock) = text.strip_suffix("pm") {
+        (clock, Some(true))
+    } else {
+        (text, None)
+    };
+
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) => (hour.parse::<u32>().ok()?, minute.parse::<u32>().ok()?),
+        None => (clock.parse::<u32>().ok()?, 0),
+    };
+
+    let hour = match meridiem {
+        Some(pm) => {
+            if !(1..=12).contains(&hour) {
+                return None;
+            }
+            match (pm, hour) {
+                (false, 12) => 0,
+                (true, 12) => 12,
+                (true, hour) => hour + 12,
+                (false, hour) => hour,
+            }
+        }
+        None => hour,
+    };
+    NaiveTime::from_hms_opt(hour, minute, 0)
+}
+
+// Parses "2h", "1h 30m", "01:30", "3 days", "an hour" or "2 weeks 1 day".
+// Returns None if the text is not a duration, and an error if it is one
+// that is too large.
+fn parse_spoken_duration(text: &str) -> Result<Option<Duration>, String> {
+    match parse_duration(&text.replace(' ', "")) {
+        Ok(duration) => return Ok(Some(duration)),
+        Err(e) if e == TOO_LARGE => return Err(e),
+        Err(_) => {}
+    }
+
+    let mut total = Duration::zero();
+    let mut words = text.split_whitespace();
+    while let Some(amount) = words.next() {
+        let amount: i64 = match amount {
+            "a" | "an" => 1,
+            number => match number.parse() {
+                Ok(number) => number,
+                Err(_) => return Ok(None),
+            },
+        };
+        let Some(unit) = words.next() else {
+            return Ok(None);
+        };
+        let unit = match unit.trim_end_matches('s') {
+            "m" | "min" | "minute" => Duration::try_minutes,
+            "h" | "hr" | "hour" => Duration::try_hours,
+            "d" | "day" => Duration::try_days,
+            "w" | "wk" | "week" => Duration::try_weeks,
+            _ => return Ok(None),
+        };
+        total = add_amount(total, amount, unit)?;
+    }
+
+    if total > Duration::zero() {
+        Ok(Some(total))
+    } else {
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Once;
+
+    // Runs the test in a fixed time zone with DST changes (Europe/Berlin
+    // leaves summer time on 2026-10-25 and enters it on 2026-03-29)
+    pub(crate) fn use_test_time_zone() {
+        static INIT: Once = Once::new();
+        // SAFETY: set once, before any test reads the local time zone
+        INIT.call_once(|| unsafe { std::env::set_var("TZ", "Europe/Berlin") });
+    }
+
+    pub(crate) fn local(text: &str) -> DateTime<Local> {
+        use_test_time_zone();
+        let naive = NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap();
+        Local.from_local_datetime(&naive).earliest().unwrap()
+    }
+
+    pub(crate) fn utc(text: &str) -> DateTime<Utc> {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_utc()
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("2h"), Ok(Duration::hours(2)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("1w2d"), Ok(Duration::days(9)));
+        assert_eq!(parse_duration("45"), Ok(Duration::minutes(45)));
+        assert_eq!(parse_duration("01:30"), Ok(Duration::minutes(90)));
+        assert_eq!(parse_duration("0:45"), Ok(Duration::minutes(45)));
+    }
+
+    #[test]
+    fn rejects_empty_and_invalid_durations() {
+        for value in [
+            "0:00", "00:00", "0m", "0h0m", "0", "", "h", "2x", "-1:30", "1:-30",
+        ] {
+            assert!(parse_duration(value).is_err(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn rejects_durations_too_large() {
+        let too_large = Err("duration too large".to_string());
+        assert_eq!(parse_duration("99999999999999w"), too_large);
+        assert_eq!(parse_duration("9223372036854775807m"), too_large);
+        assert_eq!(parse_duration("99999999999999:00"), too_large);
+        assert_eq!(parse_duration("15250284452w15250284452w"), too_large);
+
+        let now = local("2026-10-16 15:00");
+        let too_large = Err("duration too large".to_string());
+        assert_eq!(parse_wake_time("in 99999999999 weeks", now), too_large);
+        // Fits in a duration, but not in a date
+        assert_eq!(parse_wake_time("1000000000w", now), too_large);
+    }
+
+    #[test]
+    fn parses_relative_wake_times() {
+        let now = local("2026-10-16 15:00");
+        let at = |input| parse_wake_time(input, now);
+        assert_eq!(at("2h"), Ok(utc("2026-10-16 15:00")));
+        assert_eq!(at("in 1h 30m"), Ok(utc("2026-10-16 14:30")));
+        assert_eq!(at("in an hour"), Ok(utc("2026-10-16 14:00")));
+        assert_eq!(at("in 3 days"), Ok(utc("2026-10-19 13:00")));
+        assert_eq!(at("01:30"), Ok(utc("2026-10-16 14:30")));
+    }
+
+    #[test]
+    fn parses_days_and_times() {
+        // A Friday afternoon
+        let now = local("2026-10-16 15:00");
+        let at = |input| parse_wake_time(input, now);
+        assert_eq!(at("tomorrow"), Ok(utc("2026-10-17 07:00")));
+        assert_eq!(at("tomorrow 8:30pm"), Ok(utc("2026-10-17 18:30")));
+        assert_eq!(at("tonight"), Ok(utc("2026-10-16 18:00")));
+        assert_eq!(at("at 5pm"), Ok(utc("2026-10-16 15:00")));
+        assert_eq!(at("fri 16:00"), Ok(utc("2026-10-16 14:00")));
+        assert_eq!(at("mon"), Ok(utc("2026-10-19 07:00")));
+        assert_eq!(at("2026-11-02 08:30"), Ok(utc("2026-11-02 07:30")));
+        // A time of day that has passed is tomorrow
+        assert_eq!(at("9:30am"), Ok(utc("2026-10-17 07:30")));
+        assert_eq!(at("0:00"), Ok(utc("2026-10-16 22:00")));
+    }
+
+    #[test]
+    fn next_weekday_is_at_least_a_week_out() {
+        let now = local("2026-10-16 15:00");
+        let at = |input| parse_wake_time(input, now);
+        assert_eq!(at("next fri"), Ok(utc("2026-10-23 07:00")));
+        assert_eq!(at("next friday 8am"), Ok(utc("2026-10-23 06:00")));
+        assert_eq!(at("next sat"), Ok(utc("2026-10-24 07:00")));
+        assert_eq!(at("next thu"), Ok(utc("2026-10-29 08:00")));
+        assert!(at("next tomorrow").is_err());
+        assert!(at("next").is_err());
+    }
+
+    #[test]
+    fn weekday_passed_today_is_next_week_across_dst() {
+        // Summer time ends on Sunday; the wall-clock time stays 9:00
+        let now = local("2026-10-23 10:00");
+        assert_eq!(parse_wake_time("fri 9am", now), Ok(utc("2026-10-30 08:00")));
+    }
+
+    #[test]
+    fn repeated_local_times_resolve_to_the_first() {
+        use_test_time_zone();
+        assert_eq!(
+            local_to_utc(
+                NaiveDateTime::parse_from_str("2026-10-25 02:30", "%Y-%m-%d %H:%M").unwrap()
+            ),
+            utc("2026-10-25 00:30")
+        );
+    }
+
+    #[test]
+    fn skipped_local_times_resolve_an_hour_later() {
+        let now = local("2026-03-28 12:00");
+        // 02:30 does not exist on 2026-03-29; it becomes 03:30 summer time
+        assert_eq!(
+            parse_wake_time("2026-03-29 02:30", now),
+            Ok(utc("2026-03-29 01:30"))
+        );
+    }
+
+    #[test]
+    fn rejects_past_and_unknown_wake_times() {
+        let now = local("2026-10-16 15:00");
+        assert!(parse_wake_time("2020-01-01", now).is_err());
+        assert!(parse_wake_time("2026-10-16 14:00", now).is_err());
+        assert!(parse_wake_time("today 9am", now).is_err());
+        assert!(parse_wake_time("someday", now).is_err());
+        assert!(parse_wake_time("fri 25:00", now).is_err());
+        assert_eq!(parse_wake_time("  ", now), Err("no time given".to_string()));
+    }
+}
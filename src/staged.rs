@@ -0,0 +1,532 @@
+use chrono::{DateTime, Local, Utc};
+use rusqlite::{Connection, Result};
+use std::collections::HashMap;
+
+use crate::database;
+use crate::journal::{self, Snapshot};
+use crate::models::{Config, EvictionPolicy, MemoData, MemoStatus, WakeTarget};
+use crate::recurrence::Recurrence;
+use crate::review::ReviewSchedule;
+use crate::tags;
+
+// A copy of the memo state that a single operation mutates while writing to
+// an open transaction. MemoApp swaps it in only after the transaction commits,
+// so a failure midway never leaves memory and disk disagreeing.
+pub struct StagedState<'a> {
+    pub db: &'a Connection,
+    pub config: &'a Config,
+    pub workspace_id: i32,
+    pub max_hot_count: usize, // The workspace's limit, or the config default
+    pub hot_stack: Vec<i32>,
+    pub memos: HashMap<i32, MemoData>,
+}
+
+impl StagedState<'_> {
+    // Inserts a new memo; returns its id and the id of a memo evicted to cold, if any
+    pub fn insert_memo(
+        &mut self,
+        title: String,
+        body: String,
+        wake_at: Option<DateTime<Utc>>,
+    ) -> Result<(i32, Option<i32>)> {
+        // Same timestamp in the database and in memory
+        let now = Utc::now();
+        let new_id = database::add_memo(self.db, self.workspace_id, &title, &body, wake_at, now)?;
+        let tags = tags::parse_tags(&title, &body);
+        database::save_memo_tags(self.db, new_id, &tags)?;
+
+        // Add to memos map
+        let status = if wake_at.is_some() {
+            MemoStatus::Delayed
+        } else {
+            MemoStatus::Hot
+        };
+
+        self.memos.insert(
+            new_id,
+            MemoData {
+                id: new_id,
+                workspace_id: self.workspace_id,
+                title,
+                body,
+                status,
+                creation_date: now,
+                moved_to_done_date: None,
+                wake_at,
+                wake_target: WakeTarget::Hot,
+                tags,
+                pinned: false,
+                last_touched: now,
+                recurrence: None,
+                last_spotlighted_at: None,
+                spotlight_count: 0,
+                spotlight_hidden_until: None,
+                review: ReviewSchedule::default(),
+                expanded: false,
+            },
+        );
+
+        // Only add to hot stack if it's not delayed
+        let mut evicted = None;
+        if status == MemoStatus::Hot {
+            // Add to front of hot stack
+            self.hot_stack.insert(0, new_id);
+            evicted = self.evict_overflow()?;
+            database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        }
+        Ok((new_id, evicted))
+    }
+
+    pub fn update_content(&mut self, id: i32, title: String, body: String) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            database::update_memo_content(self.db, id, &title, &body)?;
+            memo.tags = tags::parse_tags(&title, &body);
+            database::save_memo_tags(self.db, id, &memo.tags)?;
+            memo.title = title;
+            memo.body = body;
+        }
+        self.touch(id)
+    }
+
+    // Records that the user just worked with a memo (for least-recently-touched eviction)
+    pub fn touch(&mut self, id: i32) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            let now = Utc::now();
+            memo.last_touched = now;
+            database::update_memo_touched(self.db, id, now)?;
+        }
+        Ok(())
+    }
+
+    // If hot stack is too big, move an unpinned memo to cold as chosen by the
+    // eviction policy. With `AskUser` the stack is left over the limit for the
+    // UI to resolve.
+    fn evict_overflow(&mut self) -> Result<Option<i32>> {
+        if self.hot_stack.len() <= self.max_hot_count {
+            return Ok(None);
+        }
+
+        // The memo that was just added or promoted sits on top; only evict it
+        // if everything else is pinned
+        let unpinned: Vec<&MemoData> = self
+            .hot_stack
+            .iter()
+            .filter_map(|id| self.memos.get(id))
+            .filter(|memo| !memo.pinned)
+            .collect();
+        let candidates = match unpinned.split_first() {
+            Some((top, rest)) if top.id == self.hot_stack[0] && !rest.is_empty() => rest,
+            _ => &unpinned[..],
+        };
+
+        let victim = match self.config.eviction_policy {
+            EvictionPolicy::BottomOfStack => candidates.last(),
+            EvictionPolicy::LeastRecentlyTouched => {
+                candidates.iter().min_by_key(|memo| memo.last_touched)
+            }
+            EvictionPolicy::OldestCreated => {
+                candidates.iter().min_by_key(|memo| memo.creation_date)
+            }
+            EvictionPolicy::AskUser => None,
+        };
+
+        if let Some(moved_id) = victim.map(|memo| memo.id) {
+            self.set_cold(moved_id)?;
+            return Ok(Some(moved_id));
+        }
+        Ok(None)
+    }
+
+    // Evicts memos until the hot stack fits the limit again, e.g. after
+    // several memos were woken at once, memos were moved in from another
+    // workspace or the limit was lowered. Returns the evicted ids.
+    pub fn trim_hot_stack(&mut self) -> Result<Vec<i32>> {
+        let mut evicted = Vec::new();
+        while let Some(id) = self.evict_overflow()? {
+            evicted.push(id);
+        }
+        Ok(evicted)
+    }
+
+    // Records that a memo was just shown in the cold spotlight
+    pub fn record_spotlight(&mut self, id: i32) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            let now = Utc::now();
+            memo.last_spotlighted_at = Some(now);
+            memo.spotlight_count += 1;
+            database::update_memo_spotlighted(self.db, id, now, memo.spotlight_count)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_spotlight_hidden(&mut self, id: i32, until: Option<DateTime<Utc>>) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.spotlight_hidden_until = until;
+            database::update_memo_spotlight_hidden(self.db, id, until)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_review(&mut self, id: i32, review: ReviewSchedule) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.review = review;
+            database::update_memo_review(self.db, id, &review)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_pinned(&mut self, id: i32, pinned: bool) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.pinned = pinned;
+            database::update_memo_pinned(self.db, id, pinned)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_recurrence(&mut self, id: i32, recurrence: Option<Recurrence>) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            database::update_memo_recurrence(self.db, id, recurrence.as_ref())?;
+            memo.recurrence = recurrence;
+        }
+        Ok(())
+    }
+
+    pub fn set_wake_target(&mut self, id: i32, target: WakeTarget) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.wake_target = target;
+            database::update_memo_wake_target(self.db, id, target)?;
+        }
+        Ok(())
+    }
+
+    // Moves a memo to another workspace. It leaves this workspace's memos
+    // once the operation is applied (see `MemoApp::mutate`).
+    pub fn set_workspace(&mut self, id: i32, workspace_id: i32) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.workspace_id = workspace_id;
+            memo.pinned = false;
+            database::update_memo_workspace(self.db, id, workspace_id)?;
+        }
+        self.hot_stack.retain(|&x| x != id);
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        Ok(())
+    }
+
+    pub fn set_cold(&mut self, id: i32) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.status = MemoStatus::Cold;
+            memo.pinned = false;
+            memo.wake_at = None;
+            database::update_memo_status(self.db, id, MemoStatus::Cold)?;
+        }
+        self.hot_stack.retain(|&x| x != id);
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        Ok(())
+    }
+
+    // Moves a memo to done. A recurring memo's next occurrence is created in
+    // Delayed and takes over the rule; returns its id.
+    pub fn set_done(&mut self, id: i32) -> Result<Option<i32>> {
+        let mut next = None;
+        if let Some(memo) = self.memos.get_mut(&id) {
+            let now = Utc::now();
+            memo.status = MemoStatus::Done;
+            memo.pinned = false;
+            memo.wake_at = None;
+            memo.moved_to_done_date = Some(now);
+            database::update_memo_status(self.db, id, MemoStatus::Done)?;
+
+            if let Some(rule) = &memo.recurrence
+                && let Some(wake_at) = rule.next_after(Local::now())
+            {
+                next = Some((
+                    rule.clone(),
+                    memo.wake_target,
+                    memo.title.clone(),
+                    memo.body.clone(),
+                    wake_at,
+                ));
+            }
+        }
+        self.hot_stack.retain(|&x| x != id);
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+
+        let Some((rule, wake_target, title, body, wake_at)) = next else {
+            return Ok(None);
+        };
+        self.set_recurrence(id, None)?;
+        let (next_id, _) = self.insert_memo(title, body, Some(wake_at))?;
+        self.set_recurrence(next_id, Some(rule))?;
+        self.set_wake_target(next_id, wake_target)?;
+        Ok(Some(next_id))
+    }
+
+    // Moves delayed memos to hot or cold as each asks. Memos woken into hot
+    // all go on the stack before anything is evicted, so they don't push each
+    // other out; returns the memos moved to cold to make room.
+    pub fn wake_memos(&mut self, ids: &[i32]) -> Result<Vec<i32>> {
+        for &id in ids {
+            match self.memos.get(&id).map(|memo| memo.wake_target) {
+                Some(WakeTarget::Hot) => self.push_hot(id)?,
+                Some(WakeTarget::Cold | WakeTarget::Spotlight) => self.set_cold(id)?,
+                None => {}
+            }
+        }
+        self.trim_hot_stack()
+    }
+
+    // Moves a memo to the top of the hot stack; returns the id of an evicted memo, if any
+    pub fn set_hot(&mut self, id: i32) -> Result<Option<i32>> {
+        if !self.memos.contains_key(&id) {
+            return Ok(None);
+        }
+        self.push_hot(id)?;
+        self.evict_overflow()
+    }
+
+    // Moves a memo to the top of the hot stack without evicting anything, so
+    // the stack may end up over the limit
+    pub fn push_hot(&mut self, id: i32) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.status = MemoStatus::Hot;
+            memo.moved_to_done_date = None;
+            memo.wake_at = None;
+            memo.last_touched = Utc::now();
+            database::update_memo_touched(self.db, id, memo.last_touched)?;
+            database::update_memo_status(self.db, id, MemoStatus::Hot)?;
+
+            // Add to front of hot stack
+            self.hot_stack.retain(|&x| x != id);
+            self.hot_stack.insert(0, id);
+            database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        }
+        Ok(())
+    }
+
+    // Takes a memo off the stack until the given time
+    pub fn set_delayed(&mut self, id: i32, until: DateTime<Utc>) -> Result<()> {
+        if let Some(memo) = self.memos.get_mut(&id) {
+            memo.status = MemoStatus::Delayed;
+            memo.pinned = false;
+            memo.wake_at = Some(until);
+            memo.moved_to_done_date = None;
+            database::update_memo_status(self.db, id, MemoStatus::Delayed)?;
+            database::update_memo_wake_at(self.db, id, until)?;
+        }
+        self.hot_stack.retain(|&x| x != id);
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        Ok(())
+    }
+
+    pub fn remove_memo(&mut self, id: i32) -> Result<()> {
+        // Remove from database
+        database::delete_memo(self.db, id)?;
+
+        // Remove from memory
+        self.memos.remove(&id);
+        self.hot_stack.retain(|&x| x != id);
+
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        Ok(())
+    }
+
+    pub fn shift_up(&mut self, id: i32) -> Result<()> {
+        if let Some(pos) = self.hot_stack.iter().position(|&x| x == id)
+            && pos > 0
+        {
+            self.hot_stack.swap(pos - 1, pos);
+            database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+            self.touch(id)?;
+        }
+        Ok(())
+    }
+
+    pub fn move_to_top(&mut self, id: i32) -> Result<()> {
+        // Remove from current position
+        self.hot_stack.retain(|&x| x != id);
+
+        // Add to front
+        self.hot_stack.insert(0, id);
+
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+        self.touch(id)
+    }
+
+    // Writes a journal snapshot back to the database and staged state. Only
+    // the fields that differ from `from` (the other side of the same entry)
+    // are restored, so later changes to the memos survive an undo or redo.
+    pub fn apply_snapshot(&mut self, snapshot: &Snapshot, from: &Snapshot) -> Result<()> {
+        for (id, state) in &snapshot.memos {
+            match state {
+                Some(memo) => {
+                    let changed_from = from
+                        .memos
+                        .iter()
+                        .find(|(other, _)| other == id)
+                        .and_then(|(_, memo)| memo.as_ref());
+                    // A memo moved to another workspace is not staged, but may
+                    // have been edited there since
+                    let live = match self.memos.get(id) {
+                        Some(live) => Some(live.clone()),
+                        None => database::load_memo(self.db, *id)?,
+                    };
+                    let memo = match (&live, changed_from) {
+                        (Some(live), Some(from)) => {
+                            journal::restore_changed_fields(live, memo, from)
+                        }
+                        // Keep UI-only state from the live memo
+                        (Some(live), None) => MemoData {
+                            expanded: live.expanded,
+                            ..memo.clone()
+                        },
+                        (None, _) => memo.clone(),
+                    };
+
+                    // Text changes are recorded as revisions like any other edit
+                    if let Some(live) = &live
+                        && (live.title != memo.title || live.body != memo.body)
+                    {
+                        database::update_memo_content(self.db, *id, &memo.title, &memo.body)?;
+                    }
+                    database::save_memo(self.db, &memo)?;
+                    if memo.workspace_id != self.workspace_id {
+                        // Redoing a move to another workspace
+                        database::update_memo_workspace(self.db, *id, memo.workspace_id)?;
+                    }
+                    self.memos.insert(*id, memo);
+                }
+                None => {
+                    database::delete_memo(self.db, *id)?;
+                    self.memos.remove(id);
+                }
+            }
+        }
+
+        // Restore the recorded order, keeping memos that became hot since (e.g. promoted
+        // delayed memos) at the top and dropping ones that are no longer hot
+        let is_hot = |id: &i32| {
+            self.memos.get(id).is_some_and(|memo| {
+                memo.status == MemoStatus::Hot && memo.workspace_id == self.workspace_id
+            })
+        };
+        let mut hot_stack: Vec<i32> = self
+            .hot_stack
+            .iter()
+            .filter(|id| is_hot(id) && !snapshot.hot_stack.contains(id))
+            .copied()
+            .collect();
+        hot_stack.extend(snapshot.hot_stack.iter().filter(|id| is_hot(id)));
+        self.hot_stack = hot_stack;
+        database::save_hot_stack(self.db, self.workspace_id, &self.hot_stack)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Staged state over a fresh in-memory database
+    fn with_staged<T>(config: Config, test: impl FnOnce(&mut StagedState) -> T) -> T {
+        let db = Connection::open_in_memory().unwrap();
+        database::create_tables(&db).unwrap();
+        let mut staged = StagedState {
+            db: &db,
+            config: &config,
+            workspace_id: 1,
+            max_hot_count: config.max_hot_count,
+            hot_stack: Vec::new(),
+            memos: HashMap::new(),
+        };
+        test(&mut staged)
+    }
+
+    fn add(staged: &mut StagedState, title: &str) -> (i32, Option<i32>) {
+        staged
+            .insert_memo(title.to_string(), String::new(), None)
+            .unwrap()
+    }
+
+    fn config(max_hot_count: usize) -> Config {
+        Config {
+            max_hot_count,
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn evicts_the_bottom_memo() {
+        with_staged(config(2), |staged| {
+            let (a, _) = add(staged, "a");
+            let (b, _) = add(staged, "b");
+            let (c, evicted) = add(staged, "c");
+            assert_eq!(evicted, Some(a));
+            assert_eq!(staged.hot_stack, vec![c, b]);
+            assert_eq!(staged.memos[&a].status, MemoStatus::Cold);
+        });
+    }
+
+    #[test]
+    fn keeps_pinned_memos_hot() {
+        with_staged(config(2), |staged| {
+            let (a, _) = add(staged, "a");
+            staged.set_pinned(a, true).unwrap();
+            let (b, _) = add(staged, "b");
+            let (c, evicted) = add(staged, "c");
+            assert_eq!(evicted, Some(b));
+            assert_eq!(staged.hot_stack, vec![c, a]);
+
+            // With everything else pinned, the new memo goes to cold
+            staged.set_pinned(c, true).unwrap();
+            let (d, evicted) = add(staged, "d");
+            assert_eq!(evicted, Some(d));
+            assert_eq!(staged.hot_stack, vec![c, a]);
+        });
+    }
+
+    // Three hot memos where the bottom one is neither the least recently
+    // touched nor the oldest, then a fourth that overflows the stack
+    fn overflow(policy: EvictionPolicy) -> (Vec<i32>, Option<i32>) {
+        let config = Config {
+            eviction_policy: policy,
+            ..config(3)
+        };
+        with_staged(config, |staged| {
+            let ids = ["a", "b", "c"].map(|title| add(staged, title).0);
+            let now = Utc::now();
+            for (id, hours_ago) in ids.iter().zip([2, 3, 1]) {
+                let memo = staged.memos.get_mut(id).unwrap();
+                memo.creation_date = now - chrono::Duration::hours(hours_ago);
+                memo.last_touched = now - chrono::Duration::hours(4 - hours_ago);
+            }
+            let (_, evicted) = add(staged, "d");
+            (ids.to_vec(), evicted)
+        })
+    }
+
+    #[test]
+    fn evicts_by_policy() {
+        let (ids, evicted) = overflow(EvictionPolicy::BottomOfStack);
+        assert_eq!(evicted, Some(ids[0]));
+        let (ids, evicted) = overflow(EvictionPolicy::OldestCreated);
+        assert_eq!(evicted, Some(ids[1]));
+        let (ids, evicted) = overflow(EvictionPolicy::LeastRecentlyTouched);
+        assert_eq!(evicted, Some(ids[2]));
+    }
+
+    #[test]
+    fn leaves_the_choice_to_the_user() {
+        let config = Config {
+            eviction_policy: EvictionPolicy::AskUser,
+            ..config(2)
+        };
+        with_staged(config, |staged| {
+            add(staged, "a");
+            add(staged, "b");
+            let (_, evicted) = add(staged, "c");
+            assert_eq!(evicted, None);
+            assert_eq!(staged.hot_stack.len(), 3);
+        });
+    }
+}
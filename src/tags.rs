@@ -0,0 +1,77 @@
+// Tags are written inline as #hashtags anywhere in a memo's title or body and
+// are stored lowercased in `memo_tags` so they can be listed and filtered.
+
+// Extracts the distinct tags in a memo's text, sorted. A tag starts with a
+// letter right after '#' (so "#12" and "# Heading" are not tags) and may
+// contain letters, digits, '_', '-' and '/'.
+pub fn parse_tags(title: &str, body: &str) -> Vec<String> {
+    let mut tags = Vec::new();
+    for text in [title, body] {
+        let mut previous = ' ';
+        let mut chars = text.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            let starts_tag = c == '#'
+                && !previous.is_alphanumeric()
+                && chars.peek().is_some_and(|(_, next)| next.is_alphabetic());
+            previous = c;
+            if !starts_tag {
+                continue;
+            }
+
+            let rest = &text[index + 1..];
+            let end = rest
+                .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '/')))
+                .unwrap_or(rest.len());
+            let tag = rest[..end].trim_end_matches(['-', '/']).to_lowercase();
+            tags.push(tag);
+        }
+    }
+
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Whether a memo's tags include the given tag or one of its sub-tags
+// (so "work" also matches "work/project")
+pub fn has_tag(tags: &[String], tag: &str) -> bool {
+    tags.iter().any(|t| {
+        t == tag
+            || t.strip_prefix(tag)
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tags() {
+        assert_eq!(
+            parse_tags("Call #Bob about #work/project-x", "See #work, #bob."),
+            vec!["bob", "work", "work/project-x"]
+        );
+        assert_eq!(
+            parse_tags("Trailing #tag- and #path/", ""),
+            vec!["path", "tag"]
+        );
+    }
+
+    #[test]
+    fn ignores_non_tags() {
+        assert_eq!(
+            parse_tags("# Heading", "Issue #12, mail@host#anchor, lone #"),
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn matches_sub_tags() {
+        let tags = vec!["work/project".to_string()];
+        assert!(has_tag(&tags, "work"));
+        assert!(has_tag(&tags, "work/project"));
+        assert!(!has_tag(&tags, "wor"));
+        assert!(!has_tag(&tags, "work/proj"));
+    }
+}
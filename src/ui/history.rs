@@ -0,0 +1,113 @@
+use crate::app::MemoApp;
+use crate::diff::{self, DiffLine};
+use crate::icons;
+use crate::models::MemoData;
+use eframe::egui;
+
+impl MemoApp {
+    pub fn render_memo_history(&mut self, ui: &mut egui::Ui, memo: &MemoData) {
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            ui.add(egui::Label::new(icons::icon_text(icons::HISTORY)));
+            ui.label(format!(
+                "History ({} revisions)",
+                self.history_revisions.len()
+            ));
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.small_button("Close").clicked() {
+                    self.close_history();
+                }
+            });
+        });
+
+        if self.history_revisions.is_empty() {
+            ui.small("No earlier versions yet.");
+            return;
+        }
+
+        // Version 0 is the current text, 1.. are revisions (newest first)
+        let versions: Vec<(String, String)> = std::iter::once((
+            "Current".to_string(),
+            Self::format_memo_text(&memo.title, &memo.body),
+        ))
+        .chain(self.history_revisions.iter().map(|r| {
+            (
+                r.revised_at.format("%Y-%m-%d %H:%M").to_string(),
+                Self::format_memo_text(&r.title, &r.body),
+            )
+        }))
+        .collect();
+
+        let mut restore = None;
+        ui.push_id(("history_list", memo.id), |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(120.0)
+                .show(ui, |ui| {
+                    for (index, (label, text)) in versions.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut self.history_compare.0, index, "A")
+                                .on_hover_text("Compare from this version");
+                            ui.radio_value(&mut self.history_compare.1, index, "B")
+                                .on_hover_text("Compare to this version");
+
+                            let title = text.lines().next().unwrap_or_default();
+                            ui.small(format!("{}  {}", label, title));
+
+                            if index > 0 {
+                                ui.with_layout(
+                                    egui::Layout::right_to_left(egui::Align::Center),
+                                    |ui| {
+                                        if ui.small_button("Restore").clicked() {
+                                            restore = Some(self.history_revisions[index - 1].id);
+                                        }
+                                    },
+                                );
+                            }
+                        });
+                    }
+                });
+        });
+
+        // Diff between the two selected versions
+        let (from, to) = self.history_compare;
+        if let (Some((_, old)), Some((_, new))) = (versions.get(from), versions.get(to)) {
+            ui.separator();
+            let added_color = egui::Color32::from_rgb(120, 200, 120);
+            let removed_color = egui::Color32::from_rgb(220, 110, 110);
+            for line in diff::line_diff(old, new) {
+                match line {
+                    DiffLine::Same(text) => {
+                        ui.add(egui::Label::new(
+                            egui::RichText::new(format!("  {}", text))
+                                .weak()
+                                .monospace(),
+                        ));
+                    }
+                    DiffLine::Added(text) => {
+                        ui.add(egui::Label::new(
+                            egui::RichText::new(format!("+ {}", text))
+                                .color(added_color)
+                                .monospace(),
+                        ));
+                    }
+                    DiffLine::Removed(text) => {
+                        ui.add(egui::Label::new(
+                            egui::RichText::new(format!("- {}", text))
+                                .color(removed_color)
+                                .monospace(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(revision_id) = restore
+            && let Err(e) = self.restore_revision(memo.id, revision_id)
+        {
+            eprintln!("Error restoring revision: {}", e);
+        }
+    }
+}
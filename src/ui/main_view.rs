@@ -1,11 +1,19 @@
-use crate::app::MemoApp;
+use crate::app::{CatchUpAction, MemoApp};
 use crate::icons;
 use crate::models::ActiveTab;
+use crate::schedule;
 
+use chrono::{Local, Utc};
 use eframe::egui;
+use std::time::Duration;
+
+// How long the "Undo" toast stays up after a destructive action
+const UNDO_TOAST_DURATION: Duration = Duration::from_secs(6);
 
 impl MemoApp {
     pub fn render_ui(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.clear_search_highlight();
+
         egui::CentralPanel::default().show(ctx, |ui| {
             // Tab buttons
             ui.horizontal(|ui| {
@@ -13,6 +21,11 @@ impl MemoApp {
                 self.render_tab_button(ui, ActiveTab::Cold, icons::COLD, "Cold");
                 self.render_tab_button(ui, ActiveTab::Done, icons::DONE, "Done");
                 self.render_tab_button(ui, ActiveTab::Delayed, icons::DELAY, "Delayed");
+                let review_label = match self.review_queue().len() {
+                    0 => "Review".to_string(),
+                    due => format!("Review ({})", due),
+                };
+                self.render_tab_button(ui, ActiveTab::Review, icons::REVIEW, &review_label);
 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     use std::sync::Once;
@@ -54,18 +67,316 @@ impl MemoApp {
                             ));
                         }
                     });
+
+                    ui.separator();
+                    self.render_workspace_menu(ui);
+                    ui.separator();
+
+                    // Undo/redo (in reverse order for right-to-left layout)
+                    let redo_label = self.redo_label().map(|l| format!("Redo: {}", l));
+                    let redo_button = ui.add_enabled(
+                        redo_label.is_some(),
+                        egui::Button::new(icons::icon_text(icons::REDO)),
+                    );
+                    if redo_button
+                        .on_hover_text(redo_label.as_deref().unwrap_or("Redo (Ctrl+Shift+Z)"))
+                        .clicked()
+                        && let Err(e) = self.redo()
+                    {
+                        eprintln!("Error redoing: {}", e);
+                    }
+
+                    let undo_label = self.undo_label().map(|l| format!("Undo: {}", l));
+                    let undo_button = ui.add_enabled(
+                        undo_label.is_some(),
+                        egui::Button::new(icons::icon_text(icons::UNDO)),
+                    );
+                    if undo_button
+                        .on_hover_text(undo_label.as_deref().unwrap_or("Undo (Ctrl+Z)"))
+                        .clicked()
+                        && let Err(e) = self.undo()
+                    {
+                        eprintln!("Error undoing: {}", e);
+                    }
                 });
             });
 
+            self.render_tag_filter_bar(ui);
+            self.render_global_search_bar(ui);
+
             ui.separator();
 
-            match self.active_tab {
-                ActiveTab::Hot => self.render_hot_tab(ui),
-                ActiveTab::Cold => self.render_cold_tab(ui),
-                ActiveTab::Done => self.render_done_tab(ui),
-                ActiveTab::Delayed => self.render_delayed_tab(ui),
+            // A global search replaces the tab content until it is cleared
+            if !self.global_search.trim().is_empty() {
+                self.render_search_results(ui);
+            } else {
+                match self.active_tab {
+                    ActiveTab::Hot => self.render_hot_tab(ui),
+                    ActiveTab::Cold => self.render_cold_tab(ui),
+                    ActiveTab::Done => self.render_done_tab(ui),
+                    ActiveTab::Delayed => self.render_delayed_tab(ui),
+                    ActiveTab::Review => self.render_review_tab(ui),
+                }
             }
         });
+
+        self.render_undo_toast(ctx);
+        self.render_eviction_dialog(ctx);
+        self.render_catch_up_dialog(ctx);
+    }
+
+    // Workspace switcher, the current workspace's hot stack limit and a field
+    // to create a new workspace
+    fn render_workspace_menu(&mut self, ui: &mut egui::Ui) {
+        let name = self
+            .current_workspace()
+            .map(|w| w.name.clone())
+            .unwrap_or_default();
+        let button = egui::Button::new((icons::icon_text(icons::WORKSPACE), name));
+        let (response, _) = egui::containers::menu::MenuButton::from_button(button).ui(ui, |ui| {
+            let mut switch_to = None;
+            for workspace in &self.workspaces {
+                if ui
+                    .selectable_label(workspace.id == self.workspace_id, &workspace.name)
+                    .clicked()
+                {
+                    switch_to = Some(workspace.id);
+                    ui.close();
+                }
+            }
+            if let Some(id) = switch_to
+                && let Err(e) = self.switch_workspace(id)
+            {
+                eprintln!("Error switching workspace: {}", e);
+            }
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let mut max_hot_count = self.max_hot_count();
+                ui.label("Hot stack limit");
+                if ui
+                    .add(egui::DragValue::new(&mut max_hot_count).range(1..=50))
+                    .changed()
+                    && let Err(e) = self.set_max_hot_count(Some(max_hot_count))
+                {
+                    eprintln!("Error updating hot stack limit: {}", e);
+                }
+            });
+
+            ui.separator();
+            ui.horizontal(|ui| {
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut self.new_workspace_name)
+                        .hint_text("New workspace")
+                        .desired_width(120.0),
+                );
+                let enter_pressed =
+                    response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                let can_add = !self.new_workspace_name.trim().is_empty();
+                if ui
+                    .add_enabled(can_add, egui::Button::new(icons::icon_text(icons::ADD)))
+                    .on_hover_text("Create and switch to it")
+                    .clicked()
+                    || (enter_pressed && can_add)
+                {
+                    let name = std::mem::take(&mut self.new_workspace_name);
+                    if let Err(e) = self.add_workspace(&name) {
+                        eprintln!("Error creating workspace: {}", e);
+                    }
+                    ui.close();
+                }
+            });
+        });
+        response.on_hover_text("Workspace");
+    }
+
+    // Delayed memos that came due before the app started, for the user to
+    // wake, snooze again, send to cold or finish in bulk
+    fn render_catch_up_dialog(&mut self, ctx: &egui::Context) {
+        if self.catch_up.is_empty() {
+            return;
+        }
+
+        let mut action = None;
+        egui::Window::new("Overdue memos")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} delayed memos are past their wake-up time:",
+                    self.catch_up.len()
+                ));
+                ui.separator();
+
+                let now = Utc::now();
+                egui::ScrollArea::vertical()
+                    .max_height(240.0)
+                    .show(ui, |ui| {
+                        for id in self.catch_up.clone() {
+                            let Some(memo) = self.memos.get(&id) else {
+                                continue;
+                            };
+                            let title = memo.title.clone();
+                            let due = memo
+                                .wake_at
+                                .map(|at| schedule::format_wake_time(at, now))
+                                .unwrap_or_default();
+                            ui.horizontal(|ui| {
+                                let mut selected = self.catch_up_selected.contains(&id);
+                                if ui.checkbox(&mut selected, "").changed() {
+                                    if selected {
+                                        self.catch_up_selected.insert(id);
+                                    } else {
+                                        self.catch_up_selected.remove(&id);
+                                    }
+                                }
+                                ui.add(egui::Label::new(title).truncate());
+                                ui.weak(format!("due {}", due));
+                            });
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui.small_button("All").clicked() {
+                        self.catch_up_selected = self.catch_up.iter().copied().collect();
+                    }
+                    if ui.small_button("None").clicked() {
+                        self.catch_up_selected.clear();
+                    }
+                });
+                ui.separator();
+
+                let any_selected = self
+                    .catch_up
+                    .iter()
+                    .any(|id| self.catch_up_selected.contains(id));
+                ui.horizontal(|ui| {
+                    if icons::button_with_icon(ui, icons::HOT, "Wake", any_selected)
+                        .on_hover_text("Move to hot (or where each memo is set to wake into)")
+                        .clicked()
+                    {
+                        action = Some(CatchUpAction::Wake);
+                    }
+                    if icons::button_with_icon(ui, icons::COLD, "Cold", any_selected).clicked() {
+                        action = Some(CatchUpAction::Cold);
+                    }
+                    if icons::button_with_icon(ui, icons::DONE, "Done", any_selected).clicked() {
+                        action = Some(CatchUpAction::Done);
+                    }
+                });
+
+                // Snooze the selection again
+                let until = schedule::parse_wake_time(&self.catch_up_snooze, Local::now());
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.catch_up_snooze)
+                            .hint_text("tomorrow 9am")
+                            .desired_width(120.0),
+                    );
+                    if icons::button_with_icon(
+                        ui,
+                        icons::SNOOZE,
+                        "Snooze",
+                        any_selected && until.is_ok(),
+                    )
+                    .clicked()
+                        && let Ok(until) = until
+                    {
+                        action = Some(CatchUpAction::Snooze(until));
+                    }
+                    if !self.catch_up_snooze.trim().is_empty() {
+                        match &until {
+                            Ok(at) => {
+                                ui.weak(schedule::format_wake_time(*at, Utc::now()));
+                            }
+                            Err(e) => {
+                                ui.colored_label(ui.visuals().warn_fg_color, e);
+                            }
+                        }
+                    }
+                });
+            });
+
+        if let Some(action) = action {
+            if let Err(e) = self.resolve_catch_up(action) {
+                eprintln!("Error handling overdue memos: {}", e);
+            }
+            if matches!(action, CatchUpAction::Snooze(_)) {
+                self.catch_up_snooze.clear();
+            }
+        }
+    }
+
+    // Under the ask-the-user eviction policy, asks which memo should go cold
+    // while the hot stack is over the limit
+    fn render_eviction_dialog(&mut self, ctx: &egui::Context) {
+        let candidates = self.eviction_candidates();
+        if candidates.is_empty() {
+            return;
+        }
+
+        egui::Window::new("Hot stack is full")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Only {} memos fit in the hot stack. Pick one to move to cold:",
+                    self.max_hot_count()
+                ));
+                ui.separator();
+
+                for id in candidates {
+                    let Some(memo) = self.memos.get(&id) else {
+                        continue;
+                    };
+                    let title = memo.title.clone();
+                    ui.horizontal(|ui| {
+                        if ui
+                            .button(icons::icon_text(icons::COLD))
+                            .on_hover_text("Move to Cold")
+                            .clicked()
+                            && let Err(e) = self.move_to_cold(id)
+                        {
+                            eprintln!("Error moving to cold: {}", e);
+                        }
+                        ui.add(egui::Label::new(title).truncate());
+                    });
+                }
+            });
+    }
+
+    fn render_undo_toast(&mut self, ctx: &egui::Context) {
+        let Some(toast) = &self.toast else {
+            return;
+        };
+
+        if toast.shown_at.elapsed() >= UNDO_TOAST_DURATION {
+            self.toast = None;
+            return;
+        }
+
+        let message = toast.message.clone();
+        let undoable = toast.undoable;
+        egui::Area::new(egui::Id::new("undo_toast"))
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -12.0))
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label(message);
+                        if undoable
+                            && icons::button_with_icon(ui, icons::UNDO, "Undo", true).clicked()
+                            && let Err(e) = self.undo()
+                        {
+                            eprintln!("Error undoing: {}", e);
+                        }
+                        if ui.small_button(icons::icon_text(icons::DELETE)).clicked() {
+                            self.toast = None;
+                        }
+                    });
+                });
+            });
     }
 
     fn render_tab_button(&mut self, ui: &mut egui::Ui, tab: ActiveTab, icon: &str, text: &str) {
@@ -1,6 +1,10 @@
 use crate::app::MemoApp;
+use crate::database;
 use crate::icons;
 use crate::models::{MemoData, MemoStatus};
+use crate::recurrence::Recurrence;
+use crate::schedule;
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Utc};
 use eframe::egui;
 
 impl MemoApp {
@@ -18,6 +22,12 @@ impl MemoApp {
         ui.group(|ui| {
             ui.set_width(ui.available_width());
 
+            // Inline editor replaces the normal view while editing
+            if self.editing_memo == Some(memo.id) {
+                self.render_memo_editor(ui, memo);
+                return;
+            }
+
             ui.horizontal(|ui| {
                 // Expand button (only if has body)
                 if !memo.body.is_empty() {
@@ -37,6 +47,9 @@ impl MemoApp {
                     };
 
                     if ui.button(icons::icon_text(expand_icon)).clicked() {
+                        if !is_expanded && let Err(e) = self.touch_memo(memo.id) {
+                            eprintln!("Error updating memo: {}", e);
+                        }
                         if is_spotlight {
                             let current_state = self
                                 .spotlight_expanded_states
@@ -53,10 +66,22 @@ impl MemoApp {
                     }
                 }
 
-                // Title
-                ui.add(egui::Label::new(&memo.title).wrap());
+                // Title, with matched terms highlighted while searching
+                match self.search_hit(memo) {
+                    Some(hit) => {
+                        let title = highlighted_text(ui, &hit.title, egui::TextStyle::Body);
+                        ui.add(egui::Label::new(title).wrap());
+                    }
+                    None => {
+                        ui.add(egui::Label::new(&memo.title).wrap());
+                    }
+                }
             });
 
+            if !memo.tags.is_empty() {
+                self.render_tag_chips(ui, &memo.tags);
+            }
+
             // Show body if expanded
             let is_expanded = if is_spotlight {
                 self.spotlight_expanded_states
@@ -70,6 +95,12 @@ impl MemoApp {
             if is_expanded && !memo.body.is_empty() {
                 ui.add(egui::Label::new(&memo.body).wrap());
                 ui.separator();
+            } else if let Some(hit) = self.search_hit(memo)
+                && hit.snippet.contains(database::MATCH_START)
+            {
+                // Where the search matched in the collapsed body
+                let snippet = highlighted_text(ui, &hit.snippet, egui::TextStyle::Small);
+                ui.add(egui::Label::new(snippet).wrap());
             }
 
             // Show dates and buttons
@@ -82,6 +113,25 @@ impl MemoApp {
                     ui.small(format!("Done: {}", done_date.format("%Y-%m-%d %H:%M")));
                 }
 
+                // Warning badge for idle memos that will soon decay to cold
+                if self.config.hot_decay_warning_hours > 0
+                    && let Some(left) = self.time_until_decay(memo)
+                    && left.num_hours() < self.config.hot_decay_warning_hours as i64
+                {
+                    let remaining = if left.num_hours() > 0 {
+                        format!("{}h", left.num_hours())
+                    } else {
+                        format!("{}m", left.num_minutes().max(0))
+                    };
+                    if icons::button_with_icon(ui, icons::WARNING, &remaining, true)
+                        .on_hover_text("Moves to cold when idle this long. Click to keep it hot.")
+                        .clicked()
+                        && let Err(e) = self.touch_memo(memo.id)
+                    {
+                        eprintln!("Error updating memo: {}", e);
+                    }
+                }
+
                 // Right side: Buttons
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     // Delete button (only for done memos, requires shift)
@@ -92,103 +142,394 @@ impl MemoApp {
                             egui::Button::new(icons::icon_text(icons::DELETE)),
                         );
 
-                        if delete_button.on_hover_text("Delete (Hold Shift)").clicked() {
-                            if let Err(e) = self.delete_memo(memo.id) {
-                                eprintln!("Error deleting memo: {}", e);
-                            }
+                        if delete_button.on_hover_text("Delete (Hold Shift)").clicked()
+                            && let Err(e) = self.delete_memo(memo.id)
+                        {
+                            eprintln!("Error deleting memo: {}", e);
                         }
                     }
 
                     // Done button (for hot/cold memos)
-                    if matches!(memo.status, MemoStatus::Hot | MemoStatus::Cold) {
-                        if ui
+                    if matches!(memo.status, MemoStatus::Hot | MemoStatus::Cold)
+                        && ui
                             .button(icons::icon_text(icons::DONE))
                             .on_hover_text("Move to Done")
                             .clicked()
-                        {
-                            if let Err(e) = self.move_to_done(memo.id) {
-                                eprintln!("Error moving to done: {}", e);
-                            }
-                        }
+                        && let Err(e) = self.move_to_done(memo.id)
+                    {
+                        eprintln!("Error moving to done: {}", e);
                     }
 
                     // Cold button (only for hot memos)
-                    if is_hot {
-                        if ui
+                    if is_hot
+                        && ui
                             .button(icons::icon_text(icons::COLD))
                             .on_hover_text("Move to Cold")
                             .clicked()
+                        && let Err(e) = self.move_to_cold(memo.id)
+                    {
+                        eprintln!("Error moving to cold: {}", e);
+                    }
+
+                    // Snooze menu (hot and cold memos)
+                    if matches!(memo.status, MemoStatus::Hot | MemoStatus::Cold) {
+                        let menu = ui.menu_button(icons::icon_text(icons::SNOOZE), |ui| {
+                            self.render_snooze_menu(ui)
+                        });
+                        menu.response.on_hover_text("Snooze");
+                        if let Some(Some(until)) = menu.inner
+                            && let Err(e) = self.delay_memo(memo.id, until, None)
                         {
-                            if let Err(e) = self.move_to_cold(memo.id) {
-                                eprintln!("Error moving to cold: {}", e);
-                            }
+                            eprintln!("Error snoozing memo: {}", e);
                         }
                     }
 
-                    // Hot button (for cold, done, and delayed memos)
-                    if matches!(
-                        memo.status,
-                        MemoStatus::Cold | MemoStatus::Done | MemoStatus::Delayed
-                    ) {
-                        if ui
-                            .button(icons::icon_text(icons::HOT))
-                            .on_hover_text("Move to Hot")
-                            .clicked()
+                    // Repeat menu (all but done memos)
+                    if memo.status != MemoStatus::Done {
+                        let button = egui::Button::new(icons::icon_text(icons::REPEAT))
+                            .selected(memo.recurrence.is_some());
+                        let menu = egui::containers::menu::MenuButton::from_button(button)
+                            .ui(ui, |ui| self.render_repeat_menu(ui, memo));
+                        let hover_text = match &memo.recurrence {
+                            Some(rule) => format!("Repeats: {}", rule.describe()),
+                            None => "Repeat".to_string(),
+                        };
+                        menu.0.on_hover_text(hover_text);
+                        if let Some(Some(recurrence)) = menu.1.map(|inner| inner.inner)
+                            && let Err(e) = self.set_recurrence(memo.id, recurrence)
                         {
-                            if let Err(e) = self.move_to_hot(memo.id) {
-                                eprintln!("Error moving to hot: {}", e);
+                            eprintln!("Error updating repeat rule: {}", e);
+                        }
+                    }
+
+                    // Move to another workspace
+                    if self.workspaces.len() > 1 {
+                        let menu = ui.menu_button(icons::icon_text(icons::WORKSPACE), |ui| {
+                            let mut chosen = None;
+                            for workspace in &self.workspaces {
+                                if workspace.id != self.workspace_id
+                                    && ui.button(&workspace.name).clicked()
+                                {
+                                    chosen = Some(workspace.id);
+                                    ui.close();
+                                }
                             }
+                            chosen
+                        });
+                        menu.response.on_hover_text("Move to workspace");
+                        if let Some(Some(workspace_id)) = menu.inner
+                            && let Err(e) = self.move_to_workspace(memo.id, workspace_id)
+                        {
+                            eprintln!("Error moving memo to workspace: {}", e);
                         }
                     }
 
-                    // Edit/Replace button (only for hot memos)
+                    // Pin button (only for hot memos)
                     if is_hot {
-                        if ui
-                            .button(icons::icon_text(icons::EDIT))
-                            .on_hover_text("Edit / Replace")
+                        let can_pin =
+                            memo.pinned || self.pinned_count() < self.config.max_pinned_count;
+                        let hover_text = if memo.pinned {
+                            "Unpin"
+                        } else {
+                            "Pin (never moved to cold when the stack is full)"
+                        };
+                        let pin_button = ui.add_enabled(
+                            can_pin,
+                            egui::Button::new(icons::icon_text(icons::PIN)).selected(memo.pinned),
+                        );
+                        if pin_button
+                            .on_hover_text(hover_text)
+                            .on_disabled_hover_text(format!(
+                                "At most {} memos can be pinned",
+                                self.config.max_pinned_count
+                            ))
                             .clicked()
+                            && let Err(e) = self.toggle_pin(memo.id)
                         {
-                            if let Err(e) = self.replace_memo(memo.id) {
-                                eprintln!("Error replacing memo: {}", e);
-                            }
+                            eprintln!("Error pinning memo: {}", e);
                         }
                     }
 
+                    // Hot button (for cold, done, and delayed memos)
+                    if matches!(
+                        memo.status,
+                        MemoStatus::Cold | MemoStatus::Done | MemoStatus::Delayed
+                    ) && ui
+                        .button(icons::icon_text(icons::HOT))
+                        .on_hover_text("Move to Hot")
+                        .clicked()
+                        && let Err(e) = self.move_to_hot(memo.id)
+                    {
+                        eprintln!("Error moving to hot: {}", e);
+                    }
+
+                    // History button (all memos)
+                    if ui
+                        .button(icons::icon_text(icons::HISTORY))
+                        .on_hover_text("History")
+                        .clicked()
+                    {
+                        if self.history_memo == Some(memo.id) {
+                            self.close_history();
+                        } else if let Err(e) = self.open_history(memo.id) {
+                            eprintln!("Error loading history: {}", e);
+                        }
+                    }
+
+                    // Edit button (all memos, edits in place)
+                    if ui
+                        .button(icons::icon_text(icons::EDIT))
+                        .on_hover_text("Edit")
+                        .clicked()
+                    {
+                        self.start_editing(memo.id);
+                    }
+
                     // Shift up button (leftmost of buttons, only for hot memos not at top)
-                    if is_hot {
-                        if let Some(pos) = self.hot_stack.iter().position(|&x| x == memo.id) {
-                            if pos > 0 {
-                                let shift_pressed = ui.input(|i| i.modifiers.shift);
-                                let button_icon = if shift_pressed {
-                                    icons::MOVE_TO_TOP
-                                } else {
-                                    icons::MOVE_UP
-                                };
-                                let hover_text = if shift_pressed {
-                                    "Move to Top"
-                                } else {
-                                    "Shift Up"
-                                };
-                                if ui
-                                    .button(icons::icon_text(button_icon))
-                                    .on_hover_text(hover_text)
-                                    .clicked()
-                                {
-                                    if shift_pressed {
-                                        if let Err(e) = self.move_to_top_in_hot(memo.id) {
-                                            eprintln!("Error moving to top: {}", e);
-                                        }
-                                    } else {
-                                        if let Err(e) = self.shift_up_in_hot(memo.id) {
-                                            eprintln!("Error shifting memo: {}", e);
-                                        }
-                                    }
+                    if is_hot
+                        && let Some(pos) = self.hot_stack.iter().position(|&x| x == memo.id)
+                        && pos > 0
+                    {
+                        let shift_pressed = ui.input(|i| i.modifiers.shift);
+                        let button_icon = if shift_pressed {
+                            icons::MOVE_TO_TOP
+                        } else {
+                            icons::MOVE_UP
+                        };
+                        let hover_text = if shift_pressed {
+                            "Move to Top"
+                        } else {
+                            "Shift Up"
+                        };
+                        if ui
+                            .button(icons::icon_text(button_icon))
+                            .on_hover_text(hover_text)
+                            .clicked()
+                        {
+                            if shift_pressed {
+                                if let Err(e) = self.move_to_top_in_hot(memo.id) {
+                                    eprintln!("Error moving to top: {}", e);
+                                }
+                            } else {
+                                if let Err(e) = self.shift_up_in_hot(memo.id) {
+                                    eprintln!("Error shifting memo: {}", e);
                                 }
                             }
                         }
                     }
                 });
             });
+
+            // Revision history panel
+            if self.history_memo == Some(memo.id) {
+                self.render_memo_history(ui, memo);
+            }
         });
     }
+
+    // Quick snooze choices; returns the chosen wake-up time once one is picked
+    fn render_snooze_menu(&mut self, ui: &mut egui::Ui) -> Option<DateTime<Utc>> {
+        let now = Local::now();
+        let presets = [
+            ("15 minutes", Utc::now() + Duration::minutes(15)),
+            ("1 hour", Utc::now() + Duration::hours(1)),
+            ("Tomorrow morning", schedule::tomorrow_morning(now)),
+            ("Next Monday", schedule::next_monday_morning(now)),
+        ];
+
+        let mut until = None;
+        for (label, at) in presets {
+            if ui
+                .button(label)
+                .on_hover_text(schedule::format_wake_time(at, Utc::now()))
+                .clicked()
+            {
+                until = Some(at);
+            }
+        }
+
+        ui.separator();
+
+        // Custom duration or time
+        let custom = schedule::parse_wake_time(&self.snooze_input, now);
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.snooze_input)
+                    .hint_text("2h, fri 14:00")
+                    .desired_width(90.0),
+            );
+            let enter_pressed =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui
+                .add_enabled(custom.is_ok(), egui::Button::new("Snooze"))
+                .clicked()
+                || enter_pressed)
+                && let Ok(custom) = custom
+            {
+                until = Some(custom);
+            }
+        });
+
+        // Live preview of the custom time
+        if !self.snooze_input.trim().is_empty() {
+            match &custom {
+                Ok(at) => ui.weak(format!(
+                    "Until {}",
+                    schedule::format_wake_time(*at, Utc::now())
+                )),
+                Err(e) => ui.colored_label(ui.visuals().warn_fg_color, e),
+            };
+        }
+
+        if until.is_some() {
+            self.snooze_input.clear();
+            ui.close();
+        }
+        until
+    }
+
+    // Repeat rule choices; returns Some(rule) once one is picked, Some(None)
+    // to stop repeating
+    fn render_repeat_menu(
+        &mut self,
+        ui: &mut egui::Ui,
+        memo: &MemoData,
+    ) -> Option<Option<Recurrence>> {
+        let now = Local::now();
+        let morning = NaiveTime::from_hms_opt(schedule::MORNING_HOUR, 0, 0).unwrap_or_default();
+        let presets = [
+            ("Daily", Recurrence::Daily(morning)),
+            ("Weekdays", Recurrence::Weekdays(morning)),
+            ("Weekly", Recurrence::Weekly(vec![now.weekday()], morning)),
+            ("Monthly", Recurrence::Monthly(now.day(), morning)),
+        ];
+
+        let mut chosen = None;
+        for (label, rule) in presets {
+            if ui.button(label).on_hover_text(rule.describe()).clicked() {
+                chosen = Some(Some(rule));
+            }
+        }
+
+        ui.separator();
+
+        // Custom rule
+        let custom = Recurrence::parse(&self.repeat_input);
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut self.repeat_input)
+                    .hint_text("weekly mon,thu 14:00")
+                    .desired_width(140.0),
+            );
+            let enter_pressed =
+                response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui
+                .add_enabled(custom.is_ok(), egui::Button::new("Repeat"))
+                .clicked()
+                || enter_pressed)
+                && let Ok(custom) = &custom
+            {
+                chosen = Some(Some(custom.clone()));
+            }
+        });
+
+        // Live preview of the custom rule
+        if !self.repeat_input.trim().is_empty() {
+            match &custom {
+                Ok(rule) => match rule.next_after(now) {
+                    Some(next) => ui.weak(format!(
+                        "{}, next {}",
+                        rule.describe(),
+                        schedule::format_wake_time(next, Utc::now())
+                    )),
+                    None => ui.colored_label(ui.visuals().warn_fg_color, "Never fires"),
+                },
+                Err(e) => ui.colored_label(ui.visuals().warn_fg_color, e),
+            };
+        }
+
+        if memo.recurrence.is_some() {
+            ui.separator();
+            if ui.button("Don't repeat").clicked() {
+                chosen = Some(None);
+            }
+        }
+
+        if chosen.is_some() {
+            self.repeat_input.clear();
+            ui.close();
+        }
+        chosen
+    }
+
+    fn render_memo_editor(&mut self, ui: &mut egui::Ui, memo: &MemoData) {
+        let text_edit_id = ui.id().with(("memo_edit", memo.id));
+        let response = ui.add(
+            egui::TextEdit::multiline(&mut self.edit_text)
+                .hint_text("First line is the title")
+                .desired_width(ui.available_width())
+                .desired_rows(2)
+                .id(text_edit_id),
+        );
+
+        // Focus the editor when it first opens
+        if self.edit_focus_pending {
+            response.request_focus();
+            self.edit_focus_pending = false;
+        }
+
+        let (save_pressed, cancel_pressed) = ui.input(|i| {
+            (
+                i.key_pressed(egui::Key::Enter) && i.modifiers.ctrl,
+                i.key_pressed(egui::Key::Escape),
+            )
+        });
+
+        ui.horizontal(|ui| {
+            let save_enabled = !self.edit_text.trim().is_empty();
+            let save_clicked =
+                icons::button_with_icon(ui, icons::DONE, "Save", save_enabled).clicked();
+            let cancel_clicked = ui.button("Cancel").clicked();
+
+            if save_enabled && (save_clicked || (save_pressed && response.has_focus())) {
+                if let Err(e) = self.save_editing() {
+                    eprintln!("Error updating memo: {}", e);
+                }
+            } else if cancel_clicked
+                || (cancel_pressed && (response.has_focus() || response.lost_focus()))
+            {
+                self.cancel_editing();
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                ui.small("Ctrl+Enter to save, Esc to cancel");
+            });
+        });
+    }
+}
+
+// Lays out search result text, emphasising the terms between match markers
+fn highlighted_text(ui: &egui::Ui, text: &str, style: egui::TextStyle) -> egui::text::LayoutJob {
+    let normal = egui::TextFormat {
+        font_id: style.resolve(ui.style()),
+        color: ui.visuals().text_color(),
+        ..Default::default()
+    };
+    let matched = egui::TextFormat {
+        color: ui.visuals().strong_text_color(),
+        background: ui.visuals().selection.bg_fill,
+        ..normal.clone()
+    };
+
+    let mut job = egui::text::LayoutJob::default();
+    let mut parts = text.split(database::MATCH_START);
+    if let Some(first) = parts.next() {
+        job.append(first, 0.0, normal.clone());
+    }
+    for part in parts {
+        let (term, rest) = part.split_once(database::MATCH_END).unwrap_or((part, ""));
+        job.append(term, 0.0, matched.clone());
+        job.append(rest, 0.0, normal.clone());
+    }
+    job
 }
@@ -1,4 +1,8 @@
+pub mod history;
 pub mod main_view;
 pub mod memo_item;
+pub mod review;
+pub mod search;
 pub mod tabs;
+pub mod tags;
 pub mod theme;
@@ -0,0 +1,80 @@
+use crate::app::MemoApp;
+use crate::icons;
+use crate::review::ReviewAnswer;
+use crate::schedule;
+
+use chrono::Utc;
+use eframe::egui;
+
+impl MemoApp {
+    // Review session: due cold memos come up one at a time
+    pub fn render_review_tab(&mut self, ui: &mut egui::Ui) {
+        let queue = self.review_queue();
+        let Some(memo) = queue.first().and_then(|id| self.memos.get(id)).cloned() else {
+            ui.label("Nothing to review right now.");
+            if let Some(next) = self.next_review_at() {
+                ui.weak(format!(
+                    "Next review: {}",
+                    schedule::format_wake_time(next, Utc::now())
+                ));
+            }
+            return;
+        };
+
+        ui.label(format!("Cold memos to review: {}", queue.len()));
+        ui.separator();
+
+        egui::ScrollArea::vertical()
+            .max_height(ui.available_height() - 48.0)
+            .show(ui, |ui| {
+                ui.heading(&memo.title);
+                if !memo.tags.is_empty() {
+                    self.render_tag_chips(ui, &memo.tags);
+                }
+                if !memo.body.is_empty() {
+                    ui.add(egui::Label::new(&memo.body).wrap());
+                }
+                ui.add_space(4.0);
+                let reviewed = match memo.review.due_at {
+                    Some(_) => format!("reviewed every {} days", memo.review.interval_days),
+                    None => "first review".to_string(),
+                };
+                ui.small(format!(
+                    "Created: {} · {}",
+                    memo.creation_date.format("%Y-%m-%d %H:%M"),
+                    reviewed
+                ));
+            });
+
+        ui.separator();
+
+        let mut answer = None;
+        ui.horizontal(|ui| {
+            if icons::button_with_icon(ui, icons::HOT, "Promote to hot", true).clicked() {
+                answer = Some(ReviewAnswer::Promote);
+            }
+            if icons::button_with_icon(ui, icons::COLD, "Still relevant", true)
+                .on_hover_text("Keep cold and review again later")
+                .clicked()
+            {
+                answer = Some(ReviewAnswer::Keep);
+            }
+            if icons::button_with_icon(ui, icons::DONE, "Done", true).clicked() {
+                answer = Some(ReviewAnswer::Done);
+            }
+            if ui
+                .button("Drop")
+                .on_hover_text("No longer relevant: move to done and stop repeating")
+                .clicked()
+            {
+                answer = Some(ReviewAnswer::Drop);
+            }
+        });
+
+        if let Some(answer) = answer
+            && let Err(e) = self.answer_review(memo.id, answer)
+        {
+            eprintln!("Error saving review: {}", e);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use crate::app::MemoApp;
+use crate::icons;
+use crate::models::MemoStatus;
+use crate::query::Query;
+use eframe::egui;
+
+impl MemoApp {
+    pub fn render_global_search_bar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(egui::Label::new(icons::icon_text(icons::SEARCH)));
+            let clear_width = if self.global_search.is_empty() {
+                0.0
+            } else {
+                30.0
+            };
+            ui.add_sized(
+                [ui.available_width() - clear_width, 20.0],
+                egui::TextEdit::singleline(&mut self.global_search).hint_text(
+                    "Search all memos (status:cold created:<2026-01-01 \"phrase\" -word title:foo)",
+                ),
+            );
+            if !self.global_search.is_empty()
+                && ui
+                    .small_button(icons::icon_text(icons::DELETE))
+                    .on_hover_text("Clear search")
+                    .clicked()
+            {
+                self.global_search.clear();
+            }
+        });
+        Self::render_query_error(ui, &self.global_search);
+    }
+
+    // Results of the global search, grouped by status
+    pub fn render_search_results(&mut self, ui: &mut egui::Ui) {
+        let search = self.global_search.clone();
+        let groups: Vec<(MemoStatus, &str, &str, Vec<_>)> = [
+            (MemoStatus::Hot, icons::HOT, "Hot"),
+            (MemoStatus::Delayed, icons::DELAY, "Delayed"),
+            (MemoStatus::Cold, icons::COLD, "Cold"),
+            (MemoStatus::Done, icons::DONE, "Done"),
+        ]
+        .into_iter()
+        .map(|(status, icon, label)| {
+            (
+                status,
+                icon,
+                label,
+                self.get_filtered_memos(status, &search),
+            )
+        })
+        .collect();
+
+        let total: usize = groups.iter().map(|(_, _, _, memos)| memos.len()).sum();
+        ui.label(format!("Results: {}", total));
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (status, icon, label, memos) in groups {
+                if memos.is_empty() {
+                    continue;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = 4.0;
+                    ui.add(egui::Label::new(icons::icon_text(icon)));
+                    ui.strong(format!("{} ({})", label, memos.len()));
+                });
+
+                for (_, memo) in memos {
+                    self.render_memo_item(ui, &memo, status == MemoStatus::Hot);
+                }
+                ui.add_space(8.0);
+            }
+        });
+    }
+
+    pub fn render_query_error(ui: &mut egui::Ui, search: &str) {
+        if let Err(e) = Query::parse(search) {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use crate::app::MemoApp;
 use crate::icons;
-use crate::models::MemoStatus;
+use crate::models::{MemoData, MemoStatus, WakeTarget};
+use crate::query::Query;
+use crate::schedule;
 
-use chrono::Utc;
+use chrono::{DateTime, Local, Utc};
 use eframe::egui;
 use rusqlite::Result;
 
@@ -14,14 +16,21 @@ impl MemoApp {
         // Validate memo input height against available space
         self.validate_memo_input_height(ui.available_height());
 
-        // Check if we have a spotlight to display
-        let has_spotlight = self.config.cold_spotlight_interval_seconds > 0
-            && self.current_spotlight_memo.is_some()
-            && self
-                .memos
-                .get(&self.current_spotlight_memo.unwrap())
-                .map(|memo| memo.status == MemoStatus::Cold)
-                .unwrap_or(false);
+        // Spotlight slots with a memo to display
+        let spotlights: Vec<(usize, MemoData)> = if self.config.cold_spotlight_interval_seconds > 0
+        {
+            self.spotlight_slots
+                .iter()
+                .enumerate()
+                .filter_map(|(index, slot)| {
+                    let memo = self.memos.get(&slot.memo?)?;
+                    (memo.status == MemoStatus::Cold && self.matches_tag_filter(memo))
+                        .then(|| (index, memo.clone()))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
 
         // Use bottom_up layout to make spotlight sticky at bottom
         ui.with_layout(egui::Layout::top_down(egui::Align::Min), |ui| {
@@ -121,9 +130,11 @@ impl MemoApp {
                 // Buttons row
                 ui.horizontal(|ui| {
                     // Add memo button (left aligned)
-                    let add_enabled = !self.new_memo_text.trim().is_empty();
-                    let delay_minutes = self.parse_delay_input();
-                    let button_text = if delay_minutes.is_some() {
+                    // An invalid delay disables adding rather than adding a hot memo
+                    let wake_at = self.parse_delay_input();
+                    let add_enabled =
+                        !self.new_memo_text.trim().is_empty() && !matches!(wake_at, Some(Err(_)));
+                    let button_text = if wake_at.is_some() {
                         "Delayed"
                     } else {
                         "Add Hot"
@@ -134,10 +145,9 @@ impl MemoApp {
                             i.key_pressed(egui::Key::Enter) && i.modifiers.ctrl && add_enabled
                         })))
                         && add_enabled
+                        && let Err(e) = self.add_parsed_memo(wake_at.clone().and_then(Result::ok))
                     {
-                        if let Err(e) = self.add_parsed_memo(delay_minutes) {
-                            eprintln!("Error adding memo: {}", e);
-                        }
+                        eprintln!("Error adding memo: {}", e);
                     }
 
                     // Right-aligned delay controls
@@ -156,23 +166,30 @@ impl MemoApp {
                             self.delay_input = format!("{:02}:{:02}", 0, 0);
                         }
 
-                        // Delay input
-                        let delay_response = ui.add_sized(
-                            [50.0, 20.0],
-                            egui::TextEdit::singleline(&mut self.delay_input).hint_text("HH:MM"),
+                        // Delay input: a duration, a day and time or a date
+                        ui.add_sized(
+                            [110.0, 20.0],
+                            egui::TextEdit::singleline(&mut self.delay_input)
+                                .hint_text("1h30m, fri 14:00"),
+                        )
+                        .on_hover_text(
+                            "Delay by a duration (2h, 01:30, in 3 days) or until a time \
+                             (tomorrow 9am, fri 14:00, at 5pm, 2026-11-02 08:30)",
                         );
 
-                        // Filter input to only allow digits and colons
-                        if delay_response.changed() {
-                            self.delay_input = self
-                                .delay_input
-                                .chars()
-                                .filter(|c| c.is_ascii_digit() || *c == ':')
-                                .collect();
-                        }
+                        ui.label(icons::icon_text(icons::DELAY));
 
-                        ui.label(icons::icon_text(icons::DELAY))
-                            .on_hover_text("Delay (HH:MM)");
+                        // Live preview of when the memo will wake up
+                        match &wake_at {
+                            Some(Ok(at)) => {
+                                ui.weak(schedule::format_wake_time(*at, Utc::now()));
+                            }
+                            Some(Err(e)) => {
+                                ui.colored_label(ui.visuals().warn_fg_color, "?")
+                                    .on_hover_text(e);
+                            }
+                            None => {}
+                        }
                         ui.separator();
                     });
                 });
@@ -231,70 +248,124 @@ impl MemoApp {
                     .show(ui, |ui| {
                         // Render hot memos first
                         for &memo_id in &self.hot_stack.clone() {
-                            if let Some(memo) = self.memos.get(&memo_id) {
+                            if let Some(memo) = self.memos.get(&memo_id)
+                                && self.matches_tag_filter(memo)
+                            {
                                 let memo_clone = memo.clone();
                                 self.render_memo_item(ui, &memo_clone, true);
                             }
                         }
 
-                        // If we have a spotlight, add it at the bottom
-                        if has_spotlight {
-                            // Calculate remaining space and add it as spacing to push spotlight down
+                        // If we have spotlights, add them at the bottom
+                        if !spotlights.is_empty() {
+                            // Calculate remaining space and add it as spacing to push spotlights down
+                            let reserved_height =
+                                self.config.cold_spotlight_bottom_spacing * spotlights.len() as f32;
                             let remaining_height = ui.available_height();
-                            if remaining_height > self.config.cold_spotlight_bottom_spacing {
+                            if remaining_height > reserved_height {
                                 // Only add space if there's enough room
-                                ui.add_space(
-                                    remaining_height - self.config.cold_spotlight_bottom_spacing,
-                                );
+                                ui.add_space(remaining_height - reserved_height);
                             }
 
-                            ui.separator();
-                            ui.push_id("cold_spotlight", |ui| {
-                                if let Some(spotlight_id) = self.current_spotlight_memo {
-                                    if let Some(memo) = self.memos.get(&spotlight_id) {
-                                        // Check if spotlight is paused or calculate remaining seconds
-                                        let timer_text = if self.is_spotlight_paused() {
-                                            "Cold Spotlight: Paused".to_string()
-                                        } else {
-                                            let remaining_seconds = if let Some(last_update) =
-                                                self.get_last_spotlight_update()
-                                            {
-                                                let elapsed = std::time::Instant::now()
-                                                    .duration_since(last_update)
-                                                    .as_secs();
-                                                self.config
-                                                    .cold_spotlight_interval_seconds
-                                                    .saturating_sub(elapsed)
-                                            } else {
-                                                0
-                                            };
-                                            format!(
-                                                "Cold Spotlight: Next in {}s",
-                                                remaining_seconds
-                                            )
-                                        };
-
-                                        ui.horizontal(|ui| {
-                                            ui.spacing_mut().item_spacing.x = 4.0;
-                                            ui.add(egui::Label::new(icons::icon_text(icons::COLD)));
-                                            ui.label(timer_text);
-                                        });
-                                        let memo_clone = memo.clone();
-                                        self.render_memo_item_with_spotlight_state(
-                                            ui,
-                                            &memo_clone,
-                                            false,
-                                            true,
-                                        );
-                                    }
-                                }
-                            });
+                            for (slot, memo) in spotlights {
+                                ui.separator();
+                                ui.push_id(("cold_spotlight", slot), |ui| {
+                                    self.render_spotlight_slot(ui, slot, &memo);
+                                });
+                            }
                         }
                     });
             });
         });
     }
 
+    fn render_spotlight_slot(&mut self, ui: &mut egui::Ui, slot: usize, memo: &MemoData) {
+        // Check if the slot is paused or calculate remaining seconds
+        let timer_text = if self.is_spotlight_paused(slot) {
+            "Cold Spotlight: Paused".to_string()
+        } else {
+            let remaining_seconds = if let Some(last_update) = self.get_last_spotlight_update(slot)
+            {
+                let elapsed = std::time::Instant::now()
+                    .duration_since(last_update)
+                    .as_secs();
+                self.config
+                    .cold_spotlight_interval_seconds
+                    .saturating_sub(elapsed)
+            } else {
+                0
+            };
+            format!("Cold Spotlight: Next in {}s", remaining_seconds)
+        };
+
+        ui.horizontal(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            ui.add(egui::Label::new(icons::icon_text(icons::COLD)));
+            ui.label(timer_text);
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                self.render_spotlight_actions(ui, slot, memo.id)
+            });
+        });
+        self.render_memo_item_with_spotlight_state(ui, memo, false, true);
+    }
+
+    // Skip, back, "not interested" and the recent spotlight list, laid out
+    // right to left
+    fn render_spotlight_actions(&mut self, ui: &mut egui::Ui, slot: usize, spotlight_id: i32) {
+        let history: Vec<(i32, String)> = self
+            .spotlight_history()
+            .into_iter()
+            .filter_map(|id| self.memos.get(&id).map(|memo| (id, memo.title.clone())))
+            .collect();
+        // The slot keeps its memo while it is being edited
+        let editing = self.is_spotlight_editing(slot);
+
+        let menu = ui.add_enabled_ui(!editing && !history.is_empty(), |ui| {
+            ui.menu_button(icons::icon_text(icons::HISTORY), |ui| {
+                let mut chosen = None;
+                for (id, title) in &history {
+                    if ui.button(title).clicked() {
+                        chosen = Some(*id);
+                        ui.close();
+                    }
+                }
+                chosen
+            })
+        });
+        menu.response.on_hover_text("Recent spotlight memos");
+        if let Some(Some(id)) = menu.inner.inner {
+            self.show_in_spotlight(slot, Some(id));
+        }
+
+        if ui
+            .button(icons::icon_text(icons::NOT_INTERESTED))
+            .on_hover_text("Not interested: hide from the spotlight for 30 days")
+            .clicked()
+            && let Err(e) = self.hide_from_spotlight(spotlight_id)
+        {
+            eprintln!("Error hiding memo from spotlight: {}", e);
+        }
+
+        if ui
+            .add_enabled(!editing, egui::Button::new(icons::icon_text(icons::SKIP)))
+            .on_hover_text("Skip")
+            .clicked()
+        {
+            self.rotate_spotlight(slot);
+        }
+
+        if ui
+            .add_enabled(
+                !editing && !history.is_empty(),
+                egui::Button::new(icons::icon_text(icons::SKIP_BACK)),
+            )
+            .on_hover_text("Back to the previous spotlight memo")
+            .clicked()
+        {
+            self.spotlight_back(slot);
+        }
+    }
+
     pub fn render_cold_tab(&mut self, ui: &mut egui::Ui) {
         // Search bar
         ui.horizontal(|ui| {
@@ -308,10 +379,12 @@ impl MemoApp {
                 egui::TextEdit::singleline(&mut self.cold_search).hint_text("Search cold memos..."),
             );
         });
+        Self::render_query_error(ui, &self.cold_search);
 
         ui.separator();
 
-        let cold_memos = self.get_filtered_memos(MemoStatus::Cold, &self.cold_search);
+        let search = self.cold_search.clone();
+        let cold_memos = self.get_filtered_memos(MemoStatus::Cold, &search);
         ui.label(format!("Cold memos: {}", cold_memos.len()));
 
         egui::ScrollArea::vertical().show(ui, |ui| {
@@ -334,10 +407,12 @@ impl MemoApp {
                 egui::TextEdit::singleline(&mut self.done_search).hint_text("Search done memos..."),
             );
         });
+        Self::render_query_error(ui, &self.done_search);
 
         ui.separator();
 
-        let done_memos = self.get_filtered_memos(MemoStatus::Done, &self.done_search);
+        let search = self.done_search.clone();
+        let done_memos = self.get_filtered_memos(MemoStatus::Done, &search);
         ui.label(format!(
             "Done memos: {} (Hold shift to delete)",
             done_memos.len()
@@ -351,12 +426,15 @@ impl MemoApp {
     }
 
     pub fn render_delayed_tab(&mut self, ui: &mut egui::Ui) {
-        let delayed_ids: Vec<i32> = self
+        // Soonest first
+        let mut delayed: Vec<(i32, Option<chrono::DateTime<Utc>>)> = self
             .memos
             .iter()
-            .filter(|(_, memo)| memo.status == MemoStatus::Delayed)
-            .map(|(&id, _)| id)
+            .filter(|(_, memo)| memo.status == MemoStatus::Delayed && self.matches_tag_filter(memo))
+            .map(|(&id, memo)| (id, memo.wake_at))
             .collect();
+        delayed.sort_by_key(|&(_, wake_at)| wake_at);
+        let delayed_ids: Vec<i32> = delayed.into_iter().map(|(id, _)| id).collect();
 
         ui.label(format!("Delayed memos: {}", delayed_ids.len()));
 
@@ -366,16 +444,14 @@ impl MemoApp {
                     let memo_clone = memo.clone();
 
                     // Show timing information
-                    if let Some(delay_minutes) = memo.delay_minutes {
+                    if let Some(promotion_time) = memo.wake_at {
                         let now = Utc::now();
-                        let promotion_time =
-                            memo.creation_date + chrono::Duration::minutes(delay_minutes as i64);
 
                         if now >= promotion_time {
                             ui.horizontal(|ui| {
                                 ui.spacing_mut().item_spacing.x = 4.0;
                                 ui.add(egui::Label::new(icons::icon_text(icons::HOT)));
-                                ui.label(&format!("Ready to promote: {}", memo.title));
+                                ui.label(format!("Ready to promote: {}", memo.title));
                             });
                         } else {
                             let remaining = promotion_time - now;
@@ -384,11 +460,21 @@ impl MemoApp {
                             let minutes = (total_seconds % 3600) / 60;
                             let seconds = total_seconds % 60;
 
-                            if hours > 0 {
+                            if hours >= 24 {
                                 ui.horizontal(|ui| {
                                     ui.spacing_mut().item_spacing.x = 4.0;
                                     ui.add(egui::Label::new(icons::icon_text(icons::DELAY)));
-                                    ui.label(&format!(
+                                    ui.label(format!(
+                                        "{} (wakes {})",
+                                        memo.title,
+                                        schedule::format_wake_time(promotion_time, now)
+                                    ));
+                                });
+                            } else if hours > 0 {
+                                ui.horizontal(|ui| {
+                                    ui.spacing_mut().item_spacing.x = 4.0;
+                                    ui.add(egui::Label::new(icons::icon_text(icons::DELAY)));
+                                    ui.label(format!(
                                         "{} (ready in {}h {}m {}s)",
                                         memo.title, hours, minutes, seconds
                                     ));
@@ -397,7 +483,7 @@ impl MemoApp {
                                 ui.horizontal(|ui| {
                                     ui.spacing_mut().item_spacing.x = 4.0;
                                     ui.add(egui::Label::new(icons::icon_text(icons::DELAY)));
-                                    ui.label(&format!(
+                                    ui.label(format!(
                                         "{} (ready in {}m {}s)",
                                         memo.title, minutes, seconds
                                     ));
@@ -406,12 +492,44 @@ impl MemoApp {
                                 ui.horizontal(|ui| {
                                     ui.spacing_mut().item_spacing.x = 4.0;
                                     ui.add(egui::Label::new(icons::icon_text(icons::DELAY)));
-                                    ui.label(&format!("{} (ready in {}s)", memo.title, seconds));
+                                    ui.label(format!("{} (ready in {}s)", memo.title, seconds));
                                 });
                             }
                         }
                     }
 
+                    ui.horizontal(|ui| {
+                        ui.spacing_mut().item_spacing.x = 4.0;
+
+                        // Where the memo goes when it wakes up
+                        ui.small("Wakes into");
+                        let mut target = memo_clone.wake_target;
+                        egui::ComboBox::from_id_salt(("wake_target", id))
+                            .selected_text(wake_target_label(target))
+                            .show_ui(ui, |ui| {
+                                for option in
+                                    [WakeTarget::Hot, WakeTarget::Cold, WakeTarget::Spotlight]
+                                {
+                                    ui.selectable_value(
+                                        &mut target,
+                                        option,
+                                        wake_target_label(option),
+                                    );
+                                }
+                            });
+                        if target != memo_clone.wake_target
+                            && let Err(e) = self.set_wake_target(id, target)
+                        {
+                            eprintln!("Error changing wake target: {}", e);
+                        }
+
+                        if let Some(rule) = &memo_clone.recurrence {
+                            ui.add_space(8.0);
+                            ui.add(egui::Label::new(icons::icon_text(icons::REPEAT)));
+                            ui.weak(rule.describe());
+                        }
+                    });
+
                     self.render_memo_item(ui, &memo_clone, false);
                     ui.separator();
                 }
@@ -419,38 +537,38 @@ impl MemoApp {
         });
     }
 
-    pub fn parse_delay_input(&self) -> Option<u32> {
-        if self.delay_input == "00:00" {
+    // None if no delay is entered, otherwise when the new memo should wake up
+    pub fn parse_delay_input(&self) -> Option<Result<DateTime<Utc>, String>> {
+        let input = self.delay_input.trim();
+        if input.is_empty() || input == "00:00" {
             return None;
         }
-
-        let parts: Vec<&str> = self.delay_input.split(':').collect();
-        if parts.len() == 2 {
-            if let (Ok(hours), Ok(minutes)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-                if hours < 100 && minutes < 100 {
-                    let total_minutes = hours * 60 + minutes;
-                    return if total_minutes > 0 {
-                        Some(total_minutes)
-                    } else {
-                        Some(0)
-                    };
-                }
-            }
-        }
-        None
+        Some(schedule::parse_wake_time(input, Local::now()))
     }
 
-    pub fn adjust_delay_input(&mut self, delta_minutes: i32) {
-        let current_minutes = self.parse_delay_input().unwrap_or(0) as i32;
-        let new_minutes = (current_minutes + delta_minutes).max(0) as u32;
+    // Adds to the entered delay, rewriting it as HH:MM from now
+    pub fn adjust_delay_input(&mut self, delta_minutes: i64) {
+        let current_minutes = match self.parse_delay_input() {
+            Some(Ok(at)) => (at - Utc::now()).num_minutes() + 1,
+            _ => 0,
+        };
+        let new_minutes = (current_minutes + delta_minutes).max(0);
 
         let hours = new_minutes / 60;
         let minutes = new_minutes % 60;
         self.delay_input = format!("{:02}:{:02}", hours, minutes);
     }
 
-    pub fn parse_memo_text(&self) -> (String, String) {
-        let text = self.new_memo_text.trim();
+    pub fn format_memo_text(title: &str, body: &str) -> String {
+        if body.is_empty() {
+            title.to_string()
+        } else {
+            format!("{}\n{}", title, body)
+        }
+    }
+
+    pub fn parse_memo_text(text: &str) -> (String, String) {
+        let text = text.trim();
         if let Some(first_newline) = text.find('\n') {
             let title = text[..first_newline].trim().to_string();
             let body = text[first_newline + 1..].trim_end().to_string();
@@ -460,37 +578,53 @@ impl MemoApp {
         }
     }
 
-    fn add_parsed_memo(&mut self, delay_minutes: Option<u32>) -> Result<()> {
-        let (title, body) = self.parse_memo_text();
-        self.add_memo(title, body, delay_minutes)?;
+    fn add_parsed_memo(&mut self, wake_at: Option<DateTime<Utc>>) -> Result<()> {
+        let (title, body) = Self::parse_memo_text(&self.new_memo_text);
+        self.add_memo(title, body, wake_at)?;
         self.new_memo_text.clear();
         Ok(())
     }
 
+    // Memos with the given status that match a search query (see `query.rs`)
     pub fn get_filtered_memos(
-        &self,
+        &mut self,
         status: MemoStatus,
         search: &str,
     ) -> Vec<(i32, crate::models::MemoData)> {
+        // Invalid queries match nothing; the search box shows the error
+        let Ok(query) = Query::parse(search) else {
+            return Vec::new();
+        };
+
+        // Text searches come back best match first
+        if let Some(ranked) = self.search_ranked(&query) {
+            return ranked
+                .iter()
+                .filter_map(|id| self.memos.get(id))
+                .filter(|memo| {
+                    memo.status == status && query.matches(memo) && self.matches_tag_filter(memo)
+                })
+                .map(|memo| (memo.id, memo.clone()))
+                .collect();
+        }
+
         let mut memos: Vec<(i32, crate::models::MemoData)> = self
             .memos
             .iter()
-            .filter(|(_, memo)| memo.status == status)
+            .filter(|(_, memo)| {
+                memo.status == status && query.matches(memo) && self.matches_tag_filter(memo)
+            })
             .map(|(&id, memo)| (id, memo.clone()))
             .collect();
 
-        if !search.trim().is_empty() {
-            let search_lower = search.to_lowercase();
-            memos.retain(|(_, memo)| {
-                memo.title.to_lowercase().contains(&search_lower)
-                    || memo.body.to_lowercase().contains(&search_lower)
-            });
-        }
-
-        // Sort by creation date (newest first) for cold, by moved_to_done_date for done
+        // Hot in stack order, cold by creation date (newest first), done by
+        // moved_to_done_date, delayed by when they are due
         match status {
+            MemoStatus::Hot => {
+                memos.sort_by_key(|m| self.hot_stack.iter().position(|&id| id == m.0));
+            }
             MemoStatus::Cold => {
-                memos.sort_by(|a, b| b.1.creation_date.cmp(&a.1.creation_date));
+                memos.sort_by_key(|m| std::cmp::Reverse(m.1.creation_date));
             }
             MemoStatus::Done => {
                 memos.sort_by(
@@ -502,9 +636,19 @@ impl MemoApp {
                     },
                 );
             }
-            _ => {}
+            MemoStatus::Delayed => {
+                memos.sort_by_key(|m| m.1.wake_at);
+            }
         }
 
         memos
     }
 }
+
+fn wake_target_label(target: WakeTarget) -> &'static str {
+    match target {
+        WakeTarget::Hot => "Hot",
+        WakeTarget::Cold => "Cold",
+        WakeTarget::Spotlight => "Cold spotlight",
+    }
+}
@@ -0,0 +1,55 @@
+use crate::app::MemoApp;
+use crate::icons;
+use eframe::egui;
+
+impl MemoApp {
+    // Row of tag chips that scope every tab; hidden until a memo has a tag
+    pub fn render_tag_filter_bar(&mut self, ui: &mut egui::Ui) {
+        let tag_counts = self.tag_counts();
+        if tag_counts.is_empty() && self.tag_filter.is_empty() {
+            return;
+        }
+
+        ui.horizontal_wrapped(|ui| {
+            ui.add(egui::Label::new(icons::icon_text(icons::TAG)))
+                .on_hover_text("Only show memos with the selected tags");
+
+            for (tag, count) in &tag_counts {
+                let selected = self.tag_filter.contains(tag);
+                if tag_chip(ui, &format!("#{} {}", tag, count), selected).clicked() {
+                    self.toggle_tag_filter(tag);
+                }
+            }
+
+            if !self.tag_filter.is_empty() && ui.small_button("Clear").clicked() {
+                for tag in self.tag_filter.clone() {
+                    self.toggle_tag_filter(&tag);
+                }
+            }
+        });
+    }
+
+    // A memo's tags; clicking one toggles it in the tag filter
+    pub fn render_tag_chips(&mut self, ui: &mut egui::Ui, tags: &[String]) {
+        ui.horizontal_wrapped(|ui| {
+            ui.spacing_mut().item_spacing.x = 4.0;
+            for tag in tags {
+                let selected = self.tag_filter.contains(tag);
+                if tag_chip(ui, &format!("#{}", tag), selected)
+                    .on_hover_text("Filter by this tag")
+                    .clicked()
+                {
+                    self.toggle_tag_filter(tag);
+                }
+            }
+        });
+    }
+}
+
+fn tag_chip(ui: &mut egui::Ui, text: &str, selected: bool) -> egui::Response {
+    ui.add(
+        egui::Button::new(egui::RichText::new(text).small())
+            .selected(selected)
+            .corner_radius(8.0),
+    )
+}